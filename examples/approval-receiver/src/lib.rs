@@ -1,4 +1,4 @@
-use near_sdk::{near_bindgen, borsh::{self, BorshDeserialize, BorshSerialize}, PanicOnDefault, AccountId, env, PromiseOrValue};
+use near_sdk::{near_bindgen, borsh::{self, BorshDeserialize, BorshSerialize}, PanicOnDefault, AccountId, env, PromiseOrValue, json_types::U128};
 use nep_246::multi_token::{approval::MultiTokenApprovalReceiver, token::TokenId};
 
 
@@ -28,12 +28,13 @@ impl MultiTokenApprovalReceiver for Contract {
     fn on_approve(
         &mut self,
         tokens: Vec<TokenId>,
+        amounts: Vec<U128>,
         owner_id: AccountId,
-        approval_id: u64,
+        approval_ids: Vec<u64>,
         msg: String,
     ) -> PromiseOrValue<String> {
 
-        env::log_str(format!("Tokens: {:?} Owner: {}, approval_id: {}", tokens, owner_id, approval_id).as_str());
+        env::log_str(format!("Tokens: {:?} Amounts: {:?} Owner: {}, approval_ids: {:?}", tokens, amounts, owner_id, approval_ids).as_str());
         env::log_str(&msg);
 
         PromiseOrValue::Value("yeeeeeeeeeeeeeeee".to_string())
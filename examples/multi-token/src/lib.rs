@@ -1,15 +1,16 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::LazyOption;
+use near_sdk::collections::{LazyOption, LookupMap};
 use near_sdk::json_types::U128;
 use near_sdk::Promise;
 use near_sdk::{
-    env, near_bindgen, require, AccountId, Balance, BorshStorageKey, PanicOnDefault, PromiseOrValue,
+    env, near_bindgen, require, AccountId, BorshStorageKey, PanicOnDefault, PromiseOrValue,
 };
 use nep_246::multi_token::metadata::MT_METADATA_SPEC;
 use nep_246::multi_token::token::{Token, TokenId};
+use nep_246::multi_token::utils::refund_deposit;
 use nep_246::multi_token::{
     core::MultiToken,
-    metadata::{MtContractMetadata, TokenMetadata},
+    metadata::{MtBaseTokenMetadata, MtContractMetadata, TokenMetadata},
 };
 
 #[near_bindgen]
@@ -26,6 +27,7 @@ enum StorageKey {
     TokenMetadata,
     Enumeration,
     Approval,
+    Holders,
 }
 
 #[near_bindgen]
@@ -50,29 +52,68 @@ impl Contract {
         require!(!env::state_exists(), "Already initialized");
         metadata.assert_valid();
 
-        Self {
-            tokens: MultiToken::new(
-                StorageKey::MultiToken,
-                owner_id,
-                Some(StorageKey::TokenMetadata),
-                Some(StorageKey::Enumeration),
-                Some(StorageKey::Approval),
-            ),
-            metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
-        }
+        let mut tokens = MultiToken::new(
+            StorageKey::MultiToken,
+            owner_id,
+            Some(StorageKey::TokenMetadata),
+            Some(StorageKey::Enumeration),
+            Some(StorageKey::Approval),
+        );
+        tokens.holders_per_token = Some(LookupMap::new(StorageKey::Holders));
+
+        Self { tokens, metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)) }
     }
 
     #[payable]
     pub fn mt_mint(
         &mut self,
         token_owner_id: AccountId,
-        token_metadata: TokenMetadata,
-        amount: Balance,
+        token_metadata: Option<TokenMetadata>,
+        amount: U128,
+        token_id: Option<TokenId>,
+        base_metadata_id: Option<String>,
     ) -> Token {
         assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized");
-        self.tokens.internal_mint(token_owner_id, Some(amount), Some(token_metadata), None)
+        match token_id {
+            Some(token_id) => self.tokens.internal_mint_with_id(
+                token_id,
+                token_owner_id,
+                Some(amount.into()),
+                token_metadata,
+                base_metadata_id,
+                None,
+            ),
+            None => self.tokens.internal_mint(
+                token_owner_id,
+                Some(amount.into()),
+                token_metadata,
+                base_metadata_id,
+                None,
+            ),
+        }
     }
 
+    #[payable]
+    pub fn mt_set_base_metadata(&mut self, base_metadata: MtBaseTokenMetadata) {
+        assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized");
+        let initial_storage_usage = env::storage_usage();
+        self.tokens.internal_set_base_metadata(&base_metadata);
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+    }
+
+    #[payable]
+    pub fn mt_mint_more(&mut self, token_id: TokenId, receiver_id: AccountId, amount: U128) {
+        assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized");
+        self.tokens.internal_mint_more(
+            token_id,
+            receiver_id,
+            amount.into(),
+            Some(env::predecessor_account_id()),
+            None,
+        )
+    }
+
+    #[payable]
     pub fn register(&mut self, token_id: TokenId, account_id: AccountId) {
         self.tokens.internal_register_account(&token_id, &account_id)
     }
@@ -80,4 +121,7 @@ impl Contract {
 
 nep_246::impl_multi_token_core!(Contract, tokens);
 nep_246::impl_multi_token_approval!(Contract, tokens);
-nep_246::impl_multi_token_enumeration!(Contract, tokens);
\ No newline at end of file
+nep_246::impl_multi_token_enumeration!(Contract, tokens);
+nep_246::impl_multi_token_enumeration_metadata!(Contract, tokens);
+nep_246::impl_multi_token_storage!(Contract, tokens);
+nep_246::impl_multi_token_metadata!(Contract, tokens, metadata);
\ No newline at end of file
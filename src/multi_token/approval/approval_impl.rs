@@ -1,12 +1,15 @@
+use std::collections::HashMap;
 
-
+use near_sdk::borsh::BorshSerialize;
+use near_sdk::json_types::U128;
+use near_sdk::collections::{UnorderedMap, UnorderedSet};
 use near_sdk::{assert_one_yocto, env, ext_contract, AccountId, Balance, Promise, require};
 
-
 use crate::multi_token::{
-    core::{MultiToken, GAS_FOR_MT_TRANSFER_CALL},
-    token::{Approval, TokenId},
-    utils::{bytes_for_approved_account_id, expect_approval, refund_deposit, Entity, unauthorized_assert},
+    core::{MultiToken, StorageKey, GAS_FOR_MT_TRANSFER_CALL},
+    events::{MtApprove, MtRevoke, MtRevokeAll},
+    token::{Approval, TokenApproval, TokenId},
+    utils::{expect_approval, Entity},
 };
 
 use super::MultiTokenApproval;
@@ -15,59 +18,88 @@ const NO_DEPOSIT: Balance = 0;
 
 #[ext_contract(ext_approval_receiver)]
 pub trait MultiTokenReceiver {
-    fn on_approve(&mut self, tokens: Vec<TokenId>, owner_id: AccountId, approval_id: u64, msg: String);
+    fn on_approve(
+        &mut self,
+        tokens: Vec<TokenId>,
+        amounts: Vec<U128>,
+        owner_id: AccountId,
+        approval_ids: Vec<u64>,
+        msg: String,
+    );
 }
 
 impl MultiTokenApproval for MultiToken {
     fn approve(
         &mut self,
         account_id: AccountId,
-        token_id: TokenId,
-        amount: Balance,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        expires_at: Option<u64>,
         msg: Option<String>,
     ) -> Option<Promise> {
-        // Get actual owner and caller
-        let owner_id = self.owner_by_id.get(&token_id).expect("This token does not exist");
+        // Deposit protects from granting approvals with a function call access key
+        require!(env::attached_deposit() >= 1, "Requires attached deposit of at least 1 yoctoNEAR");
+        require!(!token_ids.is_empty(), "Token IDs cannot be empty");
+        require!(token_ids.len() == amounts.len(), "Token IDs and amounts must have the same length");
 
-        // Check if caller is authorized
-        unauthorized_assert(&owner_id);
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > env::block_timestamp(), "Expiration must be in the future");
+        }
 
-        // Get the balance to check if user have enough tokens
-        let balance = self.balances_per_token.get(&token_id).unwrap().get(&owner_id).unwrap_or(0);
+        // Any holder of the token may approve
+        let owner_id = env::predecessor_account_id();
 
-        require!(balance >= amount, "Not enough balance to approve");
+        let mut approval_ids = Vec::with_capacity(token_ids.len());
+        let initial_storage_usage = env::storage_usage();
 
-        // Unwrap to check if approval supported
-        let approvals_by_id = expect_approval(self.approvals_by_id.as_mut(), Entity::Token);
+        for (token_id, amount) in token_ids.iter().zip(&amounts) {
+            // Get the balance to check if user have enough tokens
+            let balance = self
+                .balances_per_token
+                .get(token_id)
+                .expect("This token does not exist")
+                .get(&owner_id)
+                .unwrap_or(0);
 
-        // Get some IDs and check if approval management supported both for contract & token
-        let next_id = expect_approval(self.next_approval_id_by_id.as_mut(), Entity::Token);
-        let mut current_next_id =
-            expect_approval(next_id.get(&token_id), Entity::Token);
+            require!(balance >= amount.0, "Not enough balance to approve");
 
-        let new_approval = Approval { amount, approval_id: current_next_id };
-        env::log_str(format!("New approva: {:?}", new_approval).as_str());
+            // Get some IDs and check if approval management supported both for contract & token
+            let next_id = expect_approval(self.next_approval_id_by_id.as_mut(), Entity::Token);
+            let approval_id = expect_approval(next_id.get(token_id), Entity::Token);
 
-        // Get approvals for this token
-        let approvals = &mut approvals_by_id.get(&token_id).unwrap_or_default();
-        let old_approval_id = approvals.insert(account_id.clone(), new_approval);
+            // IDs are never reused, so a revoked approval can't be replayed
+            next_id.insert(token_id, &(approval_id + 1));
 
-        approvals_by_id.insert(&token_id, approvals);
+            if self.internal_get_approval(token_id, &owner_id, &account_id).is_none() {
+                self.assert_approval_limits(token_id, &owner_id);
+            }
 
-        env::log_str(format!("Updated approvals by id: {:?}", old_approval_id).as_str());
+            let new_approval = Approval { amount: *amount, approval_id, expires_at };
+            self.internal_put_approval(token_id, &owner_id, &account_id, &new_approval);
 
-        let used_storage =
-            if old_approval_id.is_none() { bytes_for_approved_account_id(&account_id) } else { 0 };
+            approval_ids.push(approval_id);
+        }
 
-        refund_deposit(used_storage);
+        let amounts_str: Vec<String> = amounts.iter().map(|amount| amount.0.to_string()).collect();
+        MtApprove {
+            owner_id: &owner_id,
+            account_id: &account_id,
+            token_ids: &token_ids.iter().map(|token_id| token_id.as_str()).collect::<Vec<_>>(),
+            amounts: &amounts_str.iter().map(|amount| amount.as_str()).collect::<Vec<_>>(),
+            approval_ids: &approval_ids,
+        }
+        .emit();
 
-        current_next_id += 1;
+        // Storage for the whole batch is charged at once
+        let used_storage = env::storage_usage().saturating_sub(initial_storage_usage);
+        self.internal_pay_storage(&owner_id, used_storage);
 
         msg.map(|msg| {
             ext_approval_receiver::on_approve(
-                vec![token_id],
+                token_ids,
+                amounts,
                 owner_id,
-                current_next_id,
+                approval_ids,
                 msg,
                 account_id,
                 NO_DEPOSIT,
@@ -78,54 +110,404 @@ impl MultiTokenApproval for MultiToken {
 
     fn revoke(&mut self, token: TokenId, account: AccountId) {
         assert_one_yocto();
-        
-        // It's impossible that token does not have owner, so i'll just unwrap the value
-        let owner = self.owner_by_id.get(&token).unwrap();
-
-        unauthorized_assert(&owner);
 
-        // Get all approvals for token, will panic if approval extension is not used for contract or token
-        let approvals = expect_approval(self.approvals_by_id.as_mut(), Entity::Contract);
-        let mut approvals_by_token = expect_approval(approvals.get(&token), Entity::Token);
+        // Approvals are revoked by the holder that granted them
+        let owner = env::predecessor_account_id();
 
-        // Remove approval for user & also clean map to save space it it's empty
-        approvals_by_token.remove(&account);
-        
-        if approvals_by_token.is_empty() {
-            approvals.remove(&token);
+        if self.internal_remove_approval(&token, &owner, &account).is_some() {
+            MtRevoke { owner_id: &owner, account_id: &account, token_ids: &[&token] }.emit();
         }
     }
 
     fn revoke_all(&mut self, token: TokenId) {
-        todo!()
+        assert_one_yocto();
+
+        let owner = env::predecessor_account_id();
+
+        let approved_accounts = match self.internal_approved_accounts(&token, &owner) {
+            Some(approved_accounts) => approved_accounts.to_vec(),
+            None => return,
+        };
+
+        // Remove all approvals granted by the caller
+        let initial_storage_usage = env::storage_usage();
+        for account_id in approved_accounts.iter() {
+            self.internal_delete_approval(&token, &owner, account_id);
+        }
+
+        let released_storage = initial_storage_usage.saturating_sub(env::storage_usage());
+        self.internal_release_storage(&owner, released_storage);
+        MtRevokeAll { owner_id: &owner, token_ids: &[&token] }.emit();
     }
 
     fn is_approved(
         &self,
         token: TokenId,
+        owner_id: AccountId,
         approved_account: AccountId,
-        amount: Balance,
+        amount: U128,
         approval: Option<u64>,
     ) -> bool {
+        match self.internal_get_approval(&token, &owner_id, &approved_account) {
+            Some(approve) if !approve.is_expired() && approve.amount.0 >= amount.0 => match approval {
+                Some(approval) => approve.approval_id.eq(&approval),
+                None => true,
+            },
+            _ => false,
+        }
+    }
+
+    fn mt_allowance(&self, owner_id: AccountId, spender_id: AccountId, token_id: TokenId) -> U128 {
+        self.internal_get_approval(&token_id, &owner_id, &spender_id)
+            .filter(|approval| !approval.is_expired())
+            .map(|approval| approval.amount)
+            .unwrap_or(U128(0))
+    }
+
+    fn increase_allowance(&mut self, account_id: AccountId, token_id: TokenId, amount: U128) {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+
+        let mut approval = self
+            .internal_get_approval(&token_id, &owner_id, &account_id)
+            .unwrap_or_else(|| env::panic_str("Account is not approved, use approve first"));
+
+        let new_amount = approval
+            .amount
+            .0
+            .checked_add(amount.0)
+            .unwrap_or_else(|| env::panic_str("Allowance overflow"));
+
+        let balance = self.balances_per_token.get(&token_id).unwrap().get(&owner_id).unwrap_or(0);
+        require!(balance >= new_amount, "Not enough balance to approve");
+
+        approval.amount = new_amount.into();
+        self.internal_update_approval(&token_id, &owner_id, &account_id, &approval);
+    }
+
+    fn decrease_allowance(&mut self, account_id: AccountId, token_id: TokenId, amount: U128) {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+
+        let mut approval = self
+            .internal_get_approval(&token_id, &owner_id, &account_id)
+            .unwrap_or_else(|| env::panic_str("Account is not approved"));
+
+        let new_amount = approval
+            .amount
+            .0
+            .checked_sub(amount.0)
+            .unwrap_or_else(|| env::panic_str("Allowance cannot be decreased below zero"));
+
+        if new_amount == 0 {
+            self.internal_remove_approval(&token_id, &owner_id, &account_id);
+            MtRevoke { owner_id: &owner_id, account_id: &account_id, token_ids: &[&token_id] }
+                .emit();
+        } else {
+            approval.amount = new_amount.into();
+            self.internal_update_approval(&token_id, &owner_id, &account_id, &approval);
+        }
+    }
+
+    fn prune_expired_approvals(
+        &mut self,
+        token_id: TokenId,
+        from_index: Option<u64>,
+        limit: u64,
+    ) -> u64 {
+        require!(limit != 0, "Limit cannot be 0");
+        let from_index = from_index.unwrap_or(0) as usize;
+
         let approvals = expect_approval(self.approvals_by_id.as_ref(), Entity::Contract);
+        let by_token = match approvals.get(&token_id) {
+            Some(by_token) => by_token,
+            None => return 0,
+        };
 
+        // Only `limit` approvals are read, so the gas doesn't depend on the number of approvals
+        let expired: Vec<(AccountId, AccountId)> = by_token
+            .keys_as_vector()
+            .iter()
+            .skip(from_index)
+            .zip(by_token.values_as_vector().iter().skip(from_index))
+            .take(limit as usize)
+            .filter(|(_, approval)| approval.is_expired())
+            .map(|(key, _)| key)
+            .collect();
 
-        env::log_str(format!("{:?}", approvals).as_str());
+        let mut released_storage: HashMap<AccountId, u64> = HashMap::new();
+        for (owner_id, account_id) in expired.iter() {
+            let initial_storage_usage = env::storage_usage();
+            self.internal_delete_approval(&token_id, owner_id, account_id);
+            *released_storage.entry(owner_id.clone()).or_default() +=
+                initial_storage_usage.saturating_sub(env::storage_usage());
 
-        let by_token = approvals.get(&token).unwrap_or_default();
+            MtRevoke { owner_id, account_id, token_ids: &[&token_id] }.emit();
+        }
 
-        match by_token.get(&approved_account) {
-            Some(approve) => {
-                if approve.amount.eq(&amount) {
-                    match approval {
-                        Some(approval) => approve.approval_id.eq(&approval),
-                        None => true,
-                    }
-                } else {
-                    false
-                }
-            },
-            None => false
-        } 
+        // Storage is refunded to the owners that paid for it
+        for (owner_id, bytes) in released_storage {
+            self.internal_release_storage(&owner_id, bytes);
+        }
+
+        expired.len() as u64
+    }
+
+    fn mt_approvals(
+        &self,
+        token_id: TokenId,
+        owner_id: AccountId,
+        from_index: Option<u64>,
+        limit: u64,
+    ) -> Vec<TokenApproval> {
+        require!(limit != 0, "Limit cannot be 0");
+
+        let approved_accounts = match self.internal_approved_accounts(&token_id, &owner_id) {
+            Some(approved_accounts) => approved_accounts,
+            None => return vec![],
+        };
+
+        approved_accounts
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .filter_map(|approved_account_id| {
+                let approval =
+                    self.internal_get_approval(&token_id, &owner_id, &approved_account_id)?;
+                Some(TokenApproval {
+                    token_id: token_id.clone(),
+                    owner_id: owner_id.clone(),
+                    approved_account_id,
+                    approval,
+                })
+            })
+            .collect()
+    }
+
+    fn mt_approvals_by_spender(
+        &self,
+        spender_id: AccountId,
+        from_index: Option<u64>,
+        limit: u64,
+    ) -> Vec<TokenApproval> {
+        require!(limit != 0, "Limit cannot be 0");
+
+        let approved = match expect_approval(self.approvals_by_spender.as_ref(), Entity::Contract)
+            .get(&spender_id)
+        {
+            Some(approved) => approved,
+            None => return vec![],
+        };
+
+        approved
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .filter_map(|(token_id, owner_id)| {
+                let approval = self.internal_get_approval(&token_id, &owner_id, &spender_id)?;
+                Some(TokenApproval {
+                    token_id,
+                    owner_id,
+                    approved_account_id: spender_id.clone(),
+                    approval,
+                })
+            })
+            .collect()
+    }
+}
+
+impl MultiToken {
+    /// Get approval of `account_id` granted by `owner_id` for the token
+    pub fn internal_get_approval(
+        &self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        account_id: &AccountId,
+    ) -> Option<Approval> {
+        expect_approval(self.approvals_by_id.as_ref(), Entity::Contract)
+            .get(token_id)
+            .and_then(|by_token| by_token.get(&(owner_id.clone(), account_id.clone())))
+    }
+
+    /// Accounts approved by `owner_id` for the token
+    pub fn internal_approved_accounts(
+        &self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+    ) -> Option<UnorderedSet<AccountId>> {
+        expect_approval(self.approved_accounts_by_owner.as_ref(), Entity::Contract)
+            .get(&(token_id.clone(), owner_id.clone()))
+    }
+
+    /// Check that a new approval of `owner_id` for the token fits into the configured limits
+    fn assert_approval_limits(&self, token_id: &TokenId, owner_id: &AccountId) {
+        if let Some(max) = self.max_approvals_per_token {
+            let count = expect_approval(self.approvals_by_id.as_ref(), Entity::Contract)
+                .get(token_id)
+                .map_or(0, |by_token| by_token.len());
+            require!(
+                count < max,
+                format!("Token {} cannot have more than {} approvals", token_id, max)
+            );
+        }
+
+        if let Some(max) = self.max_approvals_per_owner {
+            let count = self
+                .internal_approved_accounts(token_id, owner_id)
+                .map_or(0, |approved_accounts| approved_accounts.len());
+            require!(
+                count < max,
+                format!(
+                    "Account {} cannot grant more than {} approvals for token {}",
+                    owner_id, max, token_id
+                )
+            );
+        }
+    }
+
+    /// Replace existing approval and emit the approve event with the new amount
+    fn internal_update_approval(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        account_id: &AccountId,
+        approval: &Approval,
+    ) {
+        self.internal_put_approval(token_id, owner_id, account_id, approval);
+
+        MtApprove {
+            owner_id,
+            account_id,
+            token_ids: &[token_id],
+            amounts: &[&approval.amount.0.to_string()],
+            approval_ids: &[approval.approval_id],
+        }
+        .emit();
+    }
+
+    /// Insert or replace approval of `account_id` granted by `owner_id` for the token and keep
+    /// the indexes in sync. Doesn't check limits, charge storage or emit any events.
+    ///
+    /// Returns the replaced approval, if there was one.
+    pub fn internal_put_approval(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        account_id: &AccountId,
+        approval: &Approval,
+    ) -> Option<Approval> {
+        let approvals = expect_approval(self.approvals_by_id.as_mut(), Entity::Contract);
+        let mut by_token = approvals.get(token_id).unwrap_or_else(|| {
+            UnorderedMap::new(StorageKey::ApprovalsPerToken {
+                token_hash: env::sha256(token_id.as_bytes()),
+            })
+        });
+        let old_approval = by_token.insert(&(owner_id.clone(), account_id.clone()), approval);
+        approvals.insert(token_id, &by_token);
+
+        if old_approval.is_none() {
+            let key = (token_id.clone(), owner_id.clone());
+            let by_owner =
+                expect_approval(self.approved_accounts_by_owner.as_mut(), Entity::Contract);
+            let mut approved_accounts = by_owner.get(&key).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::ApprovedAccountsInner {
+                    token_owner_hash: env::sha256(&key.try_to_vec().unwrap()),
+                })
+            });
+            approved_accounts.insert(account_id);
+            by_owner.insert(&key, &approved_accounts);
+
+            self.internal_index_approval(token_id, owner_id, account_id);
+        }
+
+        old_approval
+    }
+
+    /// Remove approval of `account_id` granted by `owner_id` for the token and keep the
+    /// indexes in sync. Doesn't refund storage or emit any events.
+    fn internal_delete_approval(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        account_id: &AccountId,
+    ) -> Option<Approval> {
+        let approvals = expect_approval(self.approvals_by_id.as_mut(), Entity::Contract);
+        let mut by_token = approvals.get(token_id)?;
+        let removed = by_token.remove(&(owner_id.clone(), account_id.clone()))?;
+
+        if by_token.is_empty() {
+            approvals.remove(token_id);
+        } else {
+            approvals.insert(token_id, &by_token);
+        }
+
+        let key = (token_id.clone(), owner_id.clone());
+        let by_owner = expect_approval(self.approved_accounts_by_owner.as_mut(), Entity::Contract);
+        if let Some(mut approved_accounts) = by_owner.get(&key) {
+            approved_accounts.remove(account_id);
+            if approved_accounts.is_empty() {
+                by_owner.remove(&key);
+            } else {
+                by_owner.insert(&key, &approved_accounts);
+            }
+        }
+
+        self.internal_unindex_approval(token_id, owner_id, account_id);
+
+        Some(removed)
+    }
+
+    /// Remove approval of `account_id` granted by `owner_id` for the token and give back
+    /// the released storage to the owner. Doesn't emit any events.
+    ///
+    /// Returns the removed approval, if there was one.
+    pub fn internal_remove_approval(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        account_id: &AccountId,
+    ) -> Option<Approval> {
+        let initial_storage_usage = env::storage_usage();
+        let removed = self.internal_delete_approval(token_id, owner_id, account_id)?;
+
+        let released_storage = initial_storage_usage.saturating_sub(env::storage_usage());
+        self.internal_release_storage(owner_id, released_storage);
+
+        Some(removed)
+    }
+
+    /// Add the approval to the index of approvals by approved account
+    fn internal_index_approval(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        account_id: &AccountId,
+    ) {
+        let by_spender = expect_approval(self.approvals_by_spender.as_mut(), Entity::Contract);
+        let mut approved = by_spender.get(account_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::ApprovalsBySpenderInner {
+                account_hash: env::sha256(account_id.as_bytes()),
+            })
+        });
+        approved.insert(&(token_id.clone(), owner_id.clone()));
+        by_spender.insert(account_id, &approved);
+    }
+
+    /// Remove the approval from the index of approvals by approved account
+    fn internal_unindex_approval(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        account_id: &AccountId,
+    ) {
+        let by_spender = expect_approval(self.approvals_by_spender.as_mut(), Entity::Contract);
+        if let Some(mut approved) = by_spender.get(account_id) {
+            approved.remove(&(token_id.clone(), owner_id.clone()));
+            if approved.is_empty() {
+                by_spender.remove(account_id);
+            } else {
+                by_spender.insert(account_id, &approved);
+            }
+        }
     }
 }
@@ -4,33 +4,106 @@ mod receiver;
 pub use approval_impl::*;
 pub use receiver::*;
 
-use crate::multi_token::token::TokenId;
-use near_sdk::{AccountId, Balance, Promise};
+use crate::multi_token::token::{TokenApproval, TokenId};
+use near_sdk::json_types::U128;
+use near_sdk::{AccountId, Promise};
 
-/// Trait used in approval management
+/// Trait used in approval management. Approvals are granted by every holder of a token
+/// separately and only allow to spend tokens of that holder.
 /// Specs - https://github.com/shipsgold/NEPs/blob/master/specs/Standards/MultiToken/ApprovalManagement.md
 pub trait MultiTokenApproval {
-    /// Add an approved account for a specific set of tokens
+    /// Add an approved account for a specific set of tokens of the caller.
+    /// Requires at least 1 yoctoNEAR. Storage for all approvals is charged at once and
+    /// a single `on_approve` is sent to `account_id` if `msg` is given.
+    ///
+    /// # Arguments
+    /// * `account_id`: the account to add to approvals
+    /// * `token_ids`: the tokens for which to add an approval
+    /// * `amounts`: the amount of every token `account_id` may transfer
+    /// * `expires_at`: optional block timestamp in nanoseconds after which the approvals
+    ///   can't be used
+    /// * `msg`: optional string to be passed to `on_approve`
     fn approve(
         &mut self,
         account_id: AccountId,
-        token_id: TokenId,
-        amount: Balance,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        expires_at: Option<u64>,
         msg: Option<String>
     ) -> Option<Promise>;
 
-    /// Revoke an approve for specific token
+    /// Revoke an approve of the caller for specific token. Requires exactly 1 yoctoNEAR,
+    /// the released storage is refunded to the caller.
     fn revoke(&mut self, token: TokenId, account: AccountId);
 
-    /// Revoke all approves for a token
+    /// Revoke all approves of the caller for a token. Requires exactly 1 yoctoNEAR,
+    /// the released storage is refunded to the caller.
     fn revoke_all(&mut self, token: TokenId);
 
-    /// Check if account have access to transfer tokens
+    /// Check if account have access to transfer at least `amount` of tokens of `owner_id`
     fn is_approved(
         &self,
         token: TokenId,
+        owner_id: AccountId,
         approved_account: AccountId,
-        amount: Balance,
+        amount: U128,
         approval: Option<u64>,
     ) -> bool;
+
+    /// Get the remaining amount that `spender_id` may transfer from `owner_id`, 0 if not approved
+    fn mt_allowance(&self, owner_id: AccountId, spender_id: AccountId, token_id: TokenId) -> U128;
+
+    /// Increase the approved amount of an existing approval of the caller.
+    /// Requires exactly 1 yoctoNEAR.
+    fn increase_allowance(&mut self, account_id: AccountId, token_id: TokenId, amount: U128);
+
+    /// Decrease the approved amount of an existing approval of the caller. The approval is
+    /// removed if the amount reaches zero. Requires exactly 1 yoctoNEAR.
+    fn decrease_allowance(&mut self, account_id: AccountId, token_id: TokenId, amount: U128);
+
+    /// Remove expired approvals among `limit` approvals of the token starting from `from_index`,
+    /// may be called by anyone. The released storage is refunded to the owners that granted the
+    /// approvals. Removed approvals are replaced by the last ones, so the same window may be
+    /// pruned again.
+    ///
+    /// # Arguments
+    /// * `token_id`: the token which approvals are pruned
+    /// * `from_index` - Index to start from, defaults to 0 if not provided
+    /// * `limit` - The maximum number of approvals to check
+    ///
+    /// Returns the number of removed approvals.
+    fn prune_expired_approvals(
+        &mut self,
+        token_id: TokenId,
+        from_index: Option<u64>,
+        limit: u64,
+    ) -> u64;
+
+    /// Get approvals granted by `owner_id` for the token (with pagination)
+    ///
+    /// # Arguments
+    /// * `token_id`: the token for which approvals were granted
+    /// * `owner_id`: the holder that granted approvals
+    /// * `from_index` - Index to start from, defaults to 0 if not provided
+    /// * `limit` - The maximum number of approvals to return
+    fn mt_approvals(
+        &self,
+        token_id: TokenId,
+        owner_id: AccountId,
+        from_index: Option<u64>,
+        limit: u64,
+    ) -> Vec<TokenApproval>;
+
+    /// Get approvals granted to `spender_id` across all tokens and holders (with pagination)
+    ///
+    /// # Arguments
+    /// * `spender_id`: the approved account
+    /// * `from_index` - Index to start from, defaults to 0 if not provided
+    /// * `limit` - The maximum number of approvals to return
+    fn mt_approvals_by_spender(
+        &self,
+        spender_id: AccountId,
+        from_index: Option<u64>,
+        limit: u64,
+    ) -> Vec<TokenApproval>;
 }
@@ -1,4 +1,5 @@
 use crate::multi_token::token::TokenId;
+use near_sdk::json_types::U128;
 use near_sdk::AccountId;
 
 /// Approval receiver is the trait for the method called (or attempted to be called) when an MT contract adds an approval for an account.
@@ -7,8 +8,9 @@ pub trait MultiTokenApprovalReceiver {
     fn on_approve(
         &mut self,
         tokens: Vec<TokenId>,
+        amounts: Vec<U128>,
         owner_id: AccountId,
-        approval_id: u64,
+        approval_ids: Vec<u64>,
         msg: String,
     ) -> near_sdk::PromiseOrValue<String>;
 }
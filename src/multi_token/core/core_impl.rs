@@ -1,19 +1,22 @@
 use crate::multi_token::core::{MultiTokenCore, MultiTokenResolver};
-use crate::multi_token::events::{MtMint, MtTransfer};
-use crate::multi_token::metadata::TokenMetadata;
-use crate::multi_token::token::{Approval, Token, TokenId};
-use crate::multi_token::utils::refund_deposit_to_account;
+use crate::multi_token::events::{MtApprovalForAll, MtApprovalUsed, MtBurn, MtMint, MtTransfer};
+use crate::multi_token::metadata::{MtBaseTokenMetadata, TokenMetadata};
+use crate::multi_token::storage_management::StorageBalance;
+use crate::multi_token::token::{Approval, ApprovalsByOwner, Token, TokenId};
+use crate::multi_token::utils::{assert_valid_token_id, refund_deposit, refund_deposit_to_account};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LookupMap, TreeMap, UnorderedMap, UnorderedSet};
 use near_sdk::json_types::U128;
 use near_sdk::{
     assert_one_yocto, env, ext_contract, log, require, AccountId, Balance, BorshStorageKey,
-    CryptoHash, Gas, IntoStorageKey, PromiseOrValue, PromiseResult, StorageUsage,
+    CryptoHash, Gas, IntoStorageKey, Promise, PromiseOrValue, PromiseResult, StorageUsage,
 };
 use std::collections::HashMap;
 
 pub const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(5_000_000_000_000);
 pub const GAS_FOR_MT_TRANSFER_CALL: Gas = Gas(25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER.0);
+/// Extra gas `resolve_batch_transfer` needs for every token of the batch
+pub const GAS_FOR_RESOLVE_TRANSFER_PER_TOKEN: Gas = Gas(3_000_000_000_000);
 
 const NO_DEPOSIT: Balance = 0;
 
@@ -24,8 +27,18 @@ trait MtResolver {
         sender_id: AccountId,
         receiver: AccountId,
         token_id: TokenId,
+        amount: U128,
         approvals: Option<HashMap<AccountId, Approval>>,
-    ) -> Vector<Balance>;
+    ) -> U128;
+
+    fn resolve_batch_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Vec<Option<HashMap<AccountId, Approval>>>,
+    ) -> Vec<U128>;
 }
 
 #[ext_contract(ext_receiver)]
@@ -34,10 +47,19 @@ pub trait MultiTokenReceiver {
         &mut self,
         sender_id: AccountId,
         previous_owner_id: AccountId,
-        token_ids: TokenId,
-        amounts: Balance,
+        token_id: TokenId,
+        amounts: U128,
         msg: String,
-    ) -> PromiseOrValue<Balance>;
+    ) -> PromiseOrValue<U128>;
+
+    fn on_batch_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>>;
 }
 
 /// Implementation of the multi-token standard
@@ -64,20 +86,63 @@ pub struct MultiToken {
     /// Metadata for each token
     pub token_metadata_by_id: Option<LookupMap<TokenId, TokenMetadata>>,
 
+    /// Base metadata shared by series of tokens, by its ID
+    pub base_metadata_by_id: Option<LookupMap<String, MtBaseTokenMetadata>>,
+
+    /// ID of base metadata each token is linked to
+    pub base_id_by_token: Option<LookupMap<TokenId, String>>,
+
     /// All tokens owned by user
     pub tokens_per_owner: Option<LookupMap<AccountId, UnorderedSet<TokenId>>>,
 
+    /// All holders of token with positive balance. Not used unless set after construction,
+    /// before any token is minted, since keeping it up to date costs a write on every balance
+    /// change from or to zero
+    pub holders_per_token: Option<LookupMap<TokenId, UnorderedSet<AccountId>>>,
+
     /// Balance of user for given token
     pub balances_per_token: UnorderedMap<TokenId, LookupMap<AccountId, u128>>,
 
-    /// All approvals of user
-    pub approvals_by_id: Option<LookupMap<TokenId, HashMap<AccountId, Approval>>>,
+    /// Approvals for each token, by holder that granted them and approved account
+    pub approvals_by_id: Option<LookupMap<TokenId, ApprovalsByOwner>>,
+
+    /// Accounts approved by each holder of a token
+    pub approved_accounts_by_owner: Option<LookupMap<(TokenId, AccountId), UnorderedSet<AccountId>>>,
 
     /// Next id of approval
     pub next_approval_id_by_id: Option<LookupMap<TokenId, u64>>,
 
+    /// Reverse index of approvals: (token, owner) pairs by approved account
+    pub approvals_by_spender: Option<LookupMap<AccountId, UnorderedSet<(TokenId, AccountId)>>>,
+
     /// Next id for token
     pub next_token_id: u64,
+
+    /// Operators approved by owner to transfer all of owner's tokens
+    pub operators_by_owner: LookupMap<AccountId, UnorderedSet<AccountId>>,
+
+    /// Prepaid storage of each account registered through storage management
+    pub storage_balances: LookupMap<AccountId, StorageBalance>,
+
+    /// How receivers that were never registered for a token are handled on deposit
+    pub registration_policy: RegistrationPolicy,
+
+    /// Maximum number of approvals of a token, unlimited if not set
+    pub max_approvals_per_token: Option<u64>,
+
+    /// Maximum number of approvals a holder may grant for a token, unlimited if not set
+    pub max_approvals_per_owner: Option<u64>,
+}
+
+/// Policy of registration of accounts that receive a token for the first time
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegistrationPolicy {
+    /// Receiver must be registered with `internal_register_account` before receiving the token
+    Strict,
+    /// Sender's attached deposit covers the storage of new balance entries, the rest is refunded
+    SenderPays,
+    /// Storage of new balance entry is paid from the storage balance of the receiver
+    StorageBalance,
 }
 
 #[derive(BorshStorageKey, BorshSerialize)]
@@ -94,6 +159,13 @@ pub enum StorageKey {
     TotalSupply { supply: u128 },
     Balances,
     BalancesInner { token_id: Vec<u8> },
+    Operators,
+    OperatorsInner { account_hash: Vec<u8> },
+    StorageBalances,
+    ApprovalsBySpenderInner { account_hash: Vec<u8> },
+    ApprovalsPerToken { token_hash: Vec<u8> },
+    ApprovedAccountsInner { token_owner_hash: Vec<u8> },
+    HoldersInner { token_hash: Vec<u8> },
 }
 
 impl MultiToken {
@@ -110,14 +182,13 @@ impl MultiToken {
         S: IntoStorageKey,
         T: IntoStorageKey,
     {
-        let (approvals_by_id, next_approval_id_by_id) = if let Some(prefix) = approval_prefix {
-            let prefix: Vec<u8> = prefix.into_storage_key();
-            (
-                Some(LookupMap::new(prefix.clone())),
-                Some(LookupMap::new([prefix, "n".into()].concat())),
-            )
-        } else {
-            (None, None)
+        let token_metadata_prefix: Option<Vec<u8>> =
+            token_metadata_prefix.map(IntoStorageKey::into_storage_key);
+        let enumeration_prefix: Option<Vec<u8>> =
+            enumeration_prefix.map(IntoStorageKey::into_storage_key);
+        let approval_prefix: Option<Vec<u8>> = approval_prefix.map(IntoStorageKey::into_storage_key);
+        let approval_key = |suffix: &str| {
+            approval_prefix.as_ref().map(|prefix| [prefix, suffix.as_bytes()].concat())
         };
 
         Self {
@@ -125,12 +196,26 @@ impl MultiToken {
             extra_storage_in_bytes_per_emission: 0,
             owner_by_id: TreeMap::new(StorageKey::OwnerById),
             total_supply: LookupMap::new(StorageKey::TotalSupply { supply: u128::MAX }),
+            base_metadata_by_id: token_metadata_prefix
+                .as_ref()
+                .map(|prefix| LookupMap::new([prefix.as_slice(), b"b"].concat())),
+            base_id_by_token: token_metadata_prefix
+                .as_ref()
+                .map(|prefix| LookupMap::new([prefix.as_slice(), b"l"].concat())),
             token_metadata_by_id: token_metadata_prefix.map(LookupMap::new),
+            holders_per_token: None,
             tokens_per_owner: enumeration_prefix.map(LookupMap::new),
             balances_per_token: UnorderedMap::new(StorageKey::Balances),
-            approvals_by_id,
-            next_approval_id_by_id,
+            approvals_by_id: approval_key("").map(LookupMap::new),
+            approved_accounts_by_owner: approval_key("o").map(LookupMap::new),
+            next_approval_id_by_id: approval_key("n").map(LookupMap::new),
+            approvals_by_spender: approval_key("s").map(LookupMap::new),
             next_token_id: 0,
+            operators_by_owner: LookupMap::new(StorageKey::Operators),
+            storage_balances: LookupMap::new(StorageKey::StorageBalances),
+            registration_policy: RegistrationPolicy::Strict,
+            max_approvals_per_token: None,
+            max_approvals_per_owner: None,
         }
     }
 
@@ -153,31 +238,122 @@ impl MultiToken {
         }
     }
 
-    /// Add to balance of user specified amount
+    /// Add to balance of user specified amount. If user was never registered for the token,
+    /// it's registered according to `registration_policy`.
     pub fn internal_deposit(
         &mut self,
         token_id: &TokenId,
         account_id: &AccountId,
         amount: Balance,
     ) {
-        let balance = self.internal_unwrap_balance_of(token_id, account_id);
+        let balance = self
+            .balances_per_token
+            .get(token_id)
+            .expect("This token does not exist")
+            .get(account_id)
+            .unwrap_or_else(|| {
+                self.internal_register_on_deposit(token_id, account_id);
+                0
+            });
         if let Some(new) = balance.checked_add(amount) {
             let mut balances = self.balances_per_token.get(token_id).unwrap();
             balances.insert(account_id, &new);
-            self.total_supply.insert(
-                token_id,
-                &self
-                    .total_supply
-                    .get(token_id)
-                    .unwrap()
-                    .checked_add(amount)
-                    .unwrap_or_else(|| env::panic_str("Total supply overflow")),
-            );
+            self.internal_update_token_of_owner(token_id, account_id, balance, new);
         } else {
             env::panic_str("Balance overflow");
         }
     }
 
+    /// Register account that receives the token for the first time according to `registration_policy`.
+    /// With [`RegistrationPolicy::SenderPays`] storage is paid later by the public method, see
+    /// [`MultiToken::internal_refund_sender_deposit`].
+    fn internal_register_on_deposit(&mut self, token_id: &TokenId, account_id: &AccountId) {
+        if self.registration_policy == RegistrationPolicy::Strict {
+            env::panic_str(format!("The account {} is not registered", account_id).as_str());
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        self.balances_per_token.get(token_id).unwrap().insert(account_id, &0);
+
+        if self.registration_policy == RegistrationPolicy::StorageBalance {
+            self.internal_charge_storage_balance(
+                account_id,
+                env::storage_usage() - initial_storage_usage,
+            );
+        }
+    }
+
+    /// Check the deposit attached to a transfer. With [`RegistrationPolicy::SenderPays`] the
+    /// deposit may be bigger to cover registration of receivers, otherwise it must be exactly 1 yoctoNEAR.
+    pub fn assert_transfer_deposit(&self) {
+        if self.registration_policy == RegistrationPolicy::SenderPays {
+            require!(env::attached_deposit() >= 1, "Requires attached deposit of at least 1 yoctoNEAR");
+        } else {
+            assert_one_yocto();
+        }
+    }
+
+    /// With [`RegistrationPolicy::SenderPays`] charge the sender for the storage used since
+    /// `initial_storage_usage` and refund the rest of the attached deposit.
+    pub fn internal_refund_sender_deposit(&self, initial_storage_usage: StorageUsage) {
+        if self.registration_policy == RegistrationPolicy::SenderPays {
+            refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+        }
+    }
+
+    /// Transfer the token of `owner_id` and call `on_transfer` on receiver. The chain is resolved
+    /// with `resolve_transfer`, which refunds unused tokens to the owner.
+    fn internal_transfer_call(
+        &mut self,
+        sender_id: AccountId,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> Promise {
+        self.assert_transfer_deposit();
+        require!(
+            env::prepaid_gas() > GAS_FOR_MT_TRANSFER_CALL + GAS_FOR_RESOLVE_TRANSFER,
+            "GAS!GAS!GAS! I gonna to step on the gas"
+        );
+        let initial_storage_usage = env::storage_usage();
+
+        let old_approvals = self.internal_transfer(
+            &sender_id,
+            &owner_id,
+            &receiver_id,
+            &token_id,
+            approval_id,
+            amount.into(),
+            memo,
+        );
+        self.internal_refund_sender_deposit(initial_storage_usage);
+
+        ext_receiver::on_transfer(
+            sender_id,
+            owner_id.clone(),
+            token_id.clone(),
+            amount,
+            msg,
+            receiver_id.clone(),
+            NO_DEPOSIT,
+            env::prepaid_gas() - GAS_FOR_MT_TRANSFER_CALL,
+        )
+        .then(ext_self::resolve_transfer(
+            owner_id,
+            receiver_id,
+            token_id,
+            amount,
+            old_approvals,
+            env::current_account_id(),
+            NO_DEPOSIT,
+            GAS_FOR_RESOLVE_TRANSFER,
+        ))
+    }
+
     /// Subtract specified amount from user account in given token
     pub fn internal_withdraw(
         &mut self,
@@ -189,89 +365,337 @@ impl MultiToken {
         if let Some(new) = balance.checked_sub(amount) {
             let mut balances = self.balances_per_token.get(token_id).unwrap();
             balances.insert(account_id, &new);
-            self.total_supply.insert(
-                token_id,
-                &self
-                    .total_supply
-                    .get(token_id)
-                    .unwrap()
-                    .checked_sub(amount)
-                    .unwrap_or_else(|| env::panic_str("Total supply overflow")),
-            );
+            self.internal_update_token_of_owner(token_id, account_id, balance, new);
         } else {
             env::panic_str("The account doesn't have enough balance");
         }
     }
 
+    /// Keep the enumeration of tokens owned by `account_id` and of holders of the token in sync
+    /// with its balance. Storage of the enumeration isn't charged from storage balances: entries
+    /// are also added by minting and by refunds in resolvers, which must not panic, so the
+    /// account that gets the entry isn't always the one that paid for it.
+    fn internal_update_token_of_owner(
+        &mut self,
+        token_id: &TokenId,
+        account_id: &AccountId,
+        old_balance: Balance,
+        new_balance: Balance,
+    ) {
+        if (self.tokens_per_owner.is_none() && self.holders_per_token.is_none())
+            || (old_balance == 0) == (new_balance == 0)
+        {
+            return;
+        }
+
+        if new_balance > 0 {
+            self.internal_add_token_to_owner(account_id, token_id);
+        } else {
+            self.internal_remove_token_from_owner(account_id, token_id);
+        }
+    }
+
+    /// Transfer `amount` of token from `owner_id` to `receiver_id` and emit the transfer event.
+    /// `sender_id` must be the owner, an operator of the owner or an approved account. In the last
+    /// case the approved amount is decreased by `amount`.
+    ///
+    /// Returns the approval of sender before it was used, so it can be restored if transfer is reverted.
+    /// Approval spent completely is kept with zero amount, the caller must pass the returned approval
+    /// to [`MultiToken::internal_remove_spent_approvals`] or restore it once the transfer is finished.
     pub fn internal_transfer(
         &mut self,
         sender_id: &AccountId,
+        owner_id: &AccountId,
         receiver_id: &AccountId,
         token_id: &TokenId,
         approval_id: Option<u64>,
         amount: Balance,
-    ) -> (AccountId, Option<HashMap<AccountId, Approval>>) {
+        memo: Option<String>,
+    ) -> Option<HashMap<AccountId, Approval>> {
+        let approvals =
+            self.internal_move(sender_id, owner_id, receiver_id, token_id, approval_id, amount);
+
+        MultiToken::emit_transfer(
+            owner_id,
+            receiver_id,
+            std::slice::from_ref(token_id),
+            &[amount],
+            Some(sender_id),
+            memo,
+        );
+
+        approvals
+    }
+
+    /// Transfer several tokens of the same owner at once. Every token is moved through
+    /// the same checks as [`MultiToken::internal_transfer`], so the whole batch fails if
+    /// any of the transfers fails. A single `mt_transfer` event is emitted for the batch.
+    ///
+    /// Returns the used approvals of every token, in the order of `token_ids`. As with
+    /// [`MultiToken::internal_transfer`], spent approvals must be removed by the caller.
+    pub fn internal_batch_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        owner_id: &AccountId,
+        receiver_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        approval_ids: Option<Vec<Option<u64>>>,
+        memo: Option<String>,
+    ) -> Vec<Option<HashMap<AccountId, Approval>>> {
+        require!(!token_ids.is_empty(), "Token IDs cannot be empty");
+        require!(token_ids.len() == amounts.len(), "Token IDs and amounts must have the same length");
+
+        let approval_ids = approval_ids.unwrap_or_else(|| vec![None; token_ids.len()]);
+        require!(
+            token_ids.len() == approval_ids.len(),
+            "Token IDs and approval IDs must have the same length"
+        );
+
+        let old_approvals = token_ids
+            .iter()
+            .zip(amounts)
+            .zip(approval_ids)
+            .map(|((token_id, amount), approval_id)| {
+                self.internal_move(sender_id, owner_id, receiver_id, token_id, approval_id, *amount)
+            })
+            .collect();
+
+        MultiToken::emit_transfer(owner_id, receiver_id, token_ids, amounts, Some(sender_id), memo);
+
+        old_approvals
+    }
+
+    /// Checks that sender is allowed to move the token of owner and moves `amount` from the owner
+    /// to the receiver. Doesn't emit any events.
+    fn internal_move(
+        &mut self,
+        sender_id: &AccountId,
+        owner_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        approval_id: Option<u64>,
+        amount: Balance,
+    ) -> Option<HashMap<AccountId, Approval>> {
         // Safety checks
-        require!(sender_id != receiver_id);
+        require!(owner_id != receiver_id, "Sender and receiver must differ");
         require!(amount > 0);
 
-        let owner_of_token = self.owner_by_id.get(token_id).expect("Token not found");
+        require!(self.owner_by_id.contains_key(token_id), "Token not found");
 
-        let approvals = self
-            .approvals_by_id
-            .as_mut()
-            .and_then(|by_id| by_id.remove(token_id));
+        let used_approval =
+            self.internal_use_approval(sender_id, owner_id, token_id, approval_id, amount);
+
+        self.internal_withdraw(token_id, owner_id, amount);
+        self.internal_deposit(token_id, receiver_id, amount);
 
-        let owner_id = if sender_id != &owner_of_token {
-            let approved_accounts = approvals.as_ref().expect("Unauthorized");
+        used_approval
+    }
 
-            let approval = approved_accounts.get(sender_id);
+    /// Checks that sender is the owner, an operator of the owner or an approved account. In the
+    /// last case the approved amount is decreased by `amount`, an approval spent completely is kept
+    /// with zero amount until it's removed by [`MultiToken::internal_remove_spent_approvals`].
+    ///
+    /// Returns the approval of sender before it was used.
+    fn internal_use_approval(
+        &mut self,
+        sender_id: &AccountId,
+        owner_id: &AccountId,
+        token_id: &TokenId,
+        approval_id: Option<u64>,
+        amount: Balance,
+    ) -> Option<HashMap<AccountId, Approval>> {
+        if sender_id == owner_id || self.internal_is_approved_for_all(owner_id, sender_id) {
+            None
+        } else {
+            require!(self.approvals_by_id.is_some(), "Unauthorized");
 
-            if approval.is_none() {
-                env::panic_str("Sender not approved");
-            }
+            let mut approval = self
+                .internal_get_approval(token_id, owner_id, sender_id)
+                .unwrap_or_else(|| env::panic_str("Sender not approved"));
 
             require!(
-                approval_id.is_none() || approval.unwrap().approval_id == approval_id.unwrap(),
+                approval_id.is_none() || approval.approval_id == approval_id.unwrap(),
                 "The actual approval_id is different from given"
             );
-            Some(sender_id)
-        } else {
-            Some(sender_id)
-        };
+            require!(!approval.is_expired(), "Approval has expired");
+            require!(approval.amount.0 >= amount, "Not enough approved amount");
+
+            let old_approval = approval.clone();
+            approval.amount = (approval.amount.0 - amount).into();
+
+            MtApprovalUsed {
+                owner_id,
+                account_id: sender_id,
+                token_ids: &[token_id],
+                amounts: &[&amount.to_string()],
+                remaining_amounts: &[&approval.amount.0.to_string()],
+            }
+            .emit();
 
-        require!(
-            owner_id.unwrap() != receiver_id,
-            "Sender and receiver must differ"
-        );
+            // Approval spent completely is kept with zero amount until the transfer is finished,
+            // so its storage is not released before it may be restored by `resolve_transfer`
+            self.internal_put_approval(token_id, owner_id, sender_id, &approval);
 
-        let owner_id = owner_id.unwrap();
+            Some(HashMap::from([(sender_id.clone(), old_approval)]))
+        }
+    }
+
+    /// Burn `amount` of token from the balance of `owner_id`. `authorized_id` is the account
+    /// that burns tokens on behalf of the owner, if it's not the owner itself. Doesn't check
+    /// that `authorized_id` is allowed to burn, it's up to the caller.
+    pub fn internal_burn(
+        &mut self,
+        owner_id: &AccountId,
+        token_id: &TokenId,
+        amount: Balance,
+        authorized_id: Option<&AccountId>,
+        memo: Option<String>,
+    ) {
+        require!(amount > 0, "Amount to burn must be positive");
 
         self.internal_withdraw(token_id, owner_id, amount);
-        self.internal_deposit(token_id, receiver_id, amount);
+        self.internal_decrease_supply(token_id, amount);
 
-        MultiToken::emit_transfer(
+        MtBurn {
             owner_id,
-            receiver_id,
+            token_ids: &[token_id],
+            amounts: &[&amount.to_string()],
+            authorized_id: authorized_id.filter(|authorized_id| *authorized_id != owner_id),
+            memo: memo.as_deref(),
+        }
+        .emit();
+    }
+
+    /// Subtract burned amount from total supply of token
+    fn internal_decrease_supply(&mut self, token_id: &TokenId, amount: Balance) {
+        let supply = self.total_supply.get(token_id).expect("This token does not exist");
+        self.total_supply.insert(
             token_id,
-            amount,
-            Some(sender_id),
-            None,
+            &supply.checked_sub(amount).unwrap_or_else(|| env::panic_str("Total supply overflow")),
         );
+    }
+
+    /// Add token to the enumeration of tokens owned by user and user to the holders of token,
+    /// if enumeration extension and holders index are used
+    pub fn internal_add_token_to_owner(&mut self, owner_id: &AccountId, token_id: &TokenId) {
+        if let Some(per_owner) = &mut self.tokens_per_owner {
+            let mut token_ids = per_owner.get(owner_id).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::TokensPerOwner {
+                    account_hash: env::sha256(owner_id.as_bytes()),
+                })
+            });
+            if token_ids.insert(token_id) {
+                per_owner.insert(owner_id, &token_ids);
+            }
+        }
+
+        if let Some(per_token) = &mut self.holders_per_token {
+            let mut holders = per_token.get(token_id).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::HoldersInner {
+                    token_hash: env::sha256(token_id.as_bytes()),
+                })
+            });
+            if holders.insert(owner_id) {
+                per_token.insert(token_id, &holders);
+            }
+        }
+    }
+
+    /// Remove token from the enumeration of tokens owned by user and user from the holders
+    /// of token, if enumeration extension and holders index are used
+    pub fn internal_remove_token_from_owner(&mut self, owner_id: &AccountId, token_id: &TokenId) {
+        if let Some(per_owner) = &mut self.tokens_per_owner {
+            if let Some(mut token_ids) = per_owner.get(owner_id) {
+                token_ids.remove(token_id);
+                if token_ids.is_empty() {
+                    per_owner.remove(owner_id);
+                } else {
+                    per_owner.insert(owner_id, &token_ids);
+                }
+            }
+        }
 
-        (owner_id.to_owned(), approvals)
+        if let Some(per_token) = &mut self.holders_per_token {
+            if let Some(mut holders) = per_token.get(token_id) {
+                holders.remove(owner_id);
+                if holders.is_empty() {
+                    per_token.remove(token_id);
+                } else {
+                    per_token.insert(token_id, &holders);
+                }
+            }
+        }
     }
 
+    /// Check if `operator_id` may transfer all tokens of `owner_id`
+    pub fn internal_is_approved_for_all(&self, owner_id: &AccountId, operator_id: &AccountId) -> bool {
+        self.operators_by_owner
+            .get(owner_id)
+            .map(|operators| operators.contains(operator_id))
+            .unwrap_or(false)
+    }
+
+    /// Grant or revoke an approval of `operator_id` to transfer all tokens of `owner_id`.
+    /// Doesn't handle storage deposits, returns the storage usage difference instead, positive
+    /// if new storage was used.
+    pub fn internal_approval_for_all(
+        &mut self,
+        owner_id: &AccountId,
+        operator_id: &AccountId,
+        approved: bool,
+    ) -> i64 {
+        require!(owner_id != operator_id, "Owner can't be an operator of itself");
+
+        let initial_storage_usage = env::storage_usage();
+
+        let mut operators = self.operators_by_owner.get(owner_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::OperatorsInner {
+                account_hash: env::sha256(owner_id.as_bytes()),
+            })
+        });
+
+        let changed = if approved {
+            operators.insert(operator_id)
+        } else {
+            operators.remove(operator_id)
+        };
+
+        if operators.is_empty() {
+            self.operators_by_owner.remove(owner_id);
+        } else {
+            self.operators_by_owner.insert(owner_id, &operators);
+        }
+
+        if changed {
+            MtApprovalForAll { owner_id, operator_id, approved }.emit();
+        }
+
+        env::storage_usage() as i64 - initial_storage_usage as i64
+    }
+
+    /// Register account for the token. When the account registers itself, storage is paid from
+    /// its storage balance, or from the attached deposit if it doesn't have one. Registration of
+    /// another account is always paid from the attached deposit of the caller.
     pub fn internal_register_account(&mut self, token_id: &TokenId, account_id: &AccountId) {
+        let initial_storage_usage = env::storage_usage();
+
         if self
             .balances_per_token
             .get(token_id)
-            .unwrap()
+            .expect("This token does not exist")
             .insert(account_id, &0)
             .is_some()
         {
             env::panic_str("The account is already registered");
         }
+
+        let storage_used = env::storage_usage() - initial_storage_usage;
+        if &env::predecessor_account_id() == account_id {
+            self.internal_pay_storage(account_id, storage_used);
+        } else {
+            refund_deposit(storage_used);
+        }
     }
 
     pub fn internal_mint(
@@ -279,17 +703,49 @@ impl MultiToken {
         owner_id: AccountId,
         owner_amount: Option<Balance>,
         metadata: Option<TokenMetadata>,
+        base_id: Option<String>,
         refund_id: Option<AccountId>,
     ) -> Token {
-        let token =
-            self.internal_mint_with_refund(owner_id.clone(), owner_amount, metadata, refund_id);
-        MultiToken::emit_mint(&owner_id, &token.token_id, &token.supply, None);
+        let token = self.internal_mint_with_refund(
+            owner_id.clone(),
+            owner_amount,
+            metadata,
+            base_id,
+            refund_id,
+        );
+        MultiToken::emit_mint(&owner_id, &token.token_id, &token.supply.0, None);
+
+        token
+    }
+
+    /// Same as [`MultiToken::internal_mint`], but the ID of the new token is chosen by the caller
+    /// instead of the internal counter. Panics if the token with this ID already exists.
+    pub fn internal_mint_with_id(
+        &mut self,
+        token_id: TokenId,
+        owner_id: AccountId,
+        owner_amount: Option<Balance>,
+        metadata: Option<TokenMetadata>,
+        base_id: Option<String>,
+        refund_id: Option<AccountId>,
+    ) -> Token {
+        let token = self.internal_mint_with_id_and_refund(
+            token_id,
+            owner_id.clone(),
+            owner_amount,
+            metadata,
+            base_id,
+            refund_id,
+        );
+        MultiToken::emit_mint(&owner_id, &token.token_id, &token.supply.0, None);
 
         token
     }
 
     /// Mint a new token without checking:
     /// * Whether the caller id is equal to the `owner_id`
+    /// * `base_id` links the token to existing base metadata, then `token_metadata` may be omitted
+    ///   or contain only fields specific to the token.
     /// * `refund_id` will transfer the leftover balance after storage costs are calculated to the provided account.
     ///   Typically, the account will be the owner. If `None`, will not refund. This is useful for delaying refunding
     ///   until multiple tokens have been minted.
@@ -300,22 +756,42 @@ impl MultiToken {
         token_owner_id: AccountId,
         owner_amount: Option<Balance>,
         token_metadata: Option<TokenMetadata>,
+        base_id: Option<String>,
+        refund_id: Option<AccountId>,
+    ) -> Token {
+        let token_id = self.internal_next_token_id();
+        self.internal_mint_with_id_and_refund(
+            token_id,
+            token_owner_id,
+            owner_amount,
+            token_metadata,
+            base_id,
+            refund_id,
+        )
+    }
+
+    /// Same as [`MultiToken::internal_mint_with_refund`], but with the token ID chosen by the caller.
+    /// Panics if the ID is invalid or the token with this ID already exists.
+    pub fn internal_mint_with_id_and_refund(
+        &mut self,
+        token_id: TokenId,
+        token_owner_id: AccountId,
+        owner_amount: Option<Balance>,
+        token_metadata: Option<TokenMetadata>,
+        base_id: Option<String>,
         refund_id: Option<AccountId>,
     ) -> Token {
         // Remember current storage usage if refund_id is Some
         let initial_storage_usage = refund_id.map(|account_id| (account_id, env::storage_usage()));
 
-        // Panic if contract is using metadata extension and caller must provide it
-        if self.token_metadata_by_id.is_some() && token_metadata.is_none() {
+        // Panic if contract is using metadata extension and caller must provide it, tokens
+        // linked to base metadata may have no metadata of their own
+        if self.token_metadata_by_id.is_some() && token_metadata.is_none() && base_id.is_none() {
             env::panic_str("MUST provide metadata");
         }
 
-        // Increment next id of the token. Panic if it's overflowing u64::MAX
-        self.next_token_id
-            .checked_add(1)
-            .expect("u64 overflow, cannot mint any more tokens");
-
-        let token_id: TokenId = self.next_token_id.to_string();
+        assert_valid_token_id(&token_id);
+        require!(!self.owner_by_id.contains_key(&token_id), "Token with this ID already exists");
 
         // If contract uses approval management create new LookupMap for approvals
         self.next_approval_id_by_id
@@ -329,29 +805,31 @@ impl MultiToken {
         self.owner_by_id.insert(&token_id, &owner_id);
 
         // Insert new metadata
-        self.token_metadata_by_id
-            .as_mut()
-            .and_then(|by_id| by_id.insert(&token_id, &token_metadata.clone().unwrap()));
+        if let (Some(by_id), Some(token_metadata)) =
+            (self.token_metadata_by_id.as_mut(), token_metadata.as_ref())
+        {
+            by_id.insert(&token_id, token_metadata);
+        }
 
-        // Insert new supply
-        self.total_supply.insert(&token_id, &u128::MAX);
+        // Link to base metadata, storage of the link is paid together with the token
+        if let Some(base_id) = &base_id {
+            self.internal_link_token_to_base(&token_id, base_id);
+        }
+
+        // Insert new supply, it's equal to the minted amount
+        let supply = owner_amount.unwrap_or(0);
+        self.total_supply.insert(&token_id, &supply);
 
         // Insert new balance
         let mut new_set: LookupMap<AccountId, u128> = LookupMap::new(StorageKey::BalancesInner {
             token_id: env::sha256(token_id.as_bytes()),
         });
-        new_set.insert(&owner_id, &owner_amount.unwrap_or(0));
+        new_set.insert(&owner_id, &supply);
         self.balances_per_token.insert(&token_id, &new_set);
 
-        // Updates enumeration if extension is used
-        if let Some(per_owner) = &mut self.tokens_per_owner {
-            let mut token_ids = per_owner.get(&owner_id).unwrap_or_else(|| {
-                UnorderedSet::new(StorageKey::TokensPerOwner {
-                    account_hash: env::sha256(owner_id.as_bytes()),
-                })
-            });
-            token_ids.insert(&token_id);
-            per_owner.insert(&owner_id, &token_ids);
+        // Updates enumeration if extension is used, only accounts with positive balance are listed
+        if supply > 0 {
+            self.internal_add_token_to_owner(&owner_id, &token_id);
         }
 
         // Stuff for Approval Management extension, also check for presence of it first
@@ -368,7 +846,7 @@ impl MultiToken {
         Token {
             token_id,
             owner_id,
-            supply: u128::MAX,
+            supply: supply.into(),
             balances: HashMap::new(),
             metadata: token_metadata,
             approvals: approved_account_ids,
@@ -376,20 +854,80 @@ impl MultiToken {
         }
     }
 
+    /// Take the next free ID from the internal counter. IDs that were already chosen by callers
+    /// of [`MultiToken::internal_mint_with_id`] are skipped.
+    fn internal_next_token_id(&mut self) -> TokenId {
+        loop {
+            let token_id = self.next_token_id.to_string();
+
+            // Increment next id of the token. Panic if it's overflowing u64::MAX
+            self.next_token_id = self
+                .next_token_id
+                .checked_add(1)
+                .expect("u64 overflow, cannot mint any more tokens");
+
+            if !self.owner_by_id.contains_key(&token_id) {
+                return token_id;
+            }
+        }
+    }
+
+    /// Mint more of already existing token. Receiver will be registered for the token if needed.
+    /// * `refund_id` will transfer the leftover balance after storage costs are calculated to the provided account.
+    ///   If `None`, will not refund. This is useful for delaying refunding until multiple tokens have been minted.
+    pub fn internal_mint_more(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        amount: Balance,
+        refund_id: Option<AccountId>,
+        memo: Option<String>,
+    ) {
+        require!(amount > 0, "Amount to mint must be positive");
+
+        // Remember current storage usage if refund_id is Some
+        let initial_storage_usage = refund_id.map(|account_id| (account_id, env::storage_usage()));
+
+        let supply = self.total_supply.get(&token_id).expect("This token does not exist");
+        self.total_supply.insert(
+            &token_id,
+            &supply.checked_add(amount).unwrap_or_else(|| env::panic_str("Total supply overflow")),
+        );
+
+        let mut balances = self.balances_per_token.get(&token_id).unwrap();
+        let balance = balances.get(&receiver_id).unwrap_or(0);
+        balances.insert(
+            &receiver_id,
+            &balance.checked_add(amount).unwrap_or_else(|| env::panic_str("Balance overflow")),
+        );
+
+        self.internal_add_token_to_owner(&receiver_id, &token_id);
+
+        if let Some((id, usage)) = initial_storage_usage {
+            refund_deposit_to_account(env::storage_usage().saturating_sub(usage), id);
+        }
+
+        MultiToken::emit_mint(&receiver_id, &token_id, &amount, memo);
+    }
+
     fn emit_transfer(
         owner_id: &AccountId,
         receiver_id: &AccountId,
-        token_id: &str,
-        amount: Balance,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
         sender_id: Option<&AccountId>,
         memo: Option<String>,
     ) {
+        let token_ids: Vec<&str> = token_ids.iter().map(|token_id| token_id.as_str()).collect();
+        let amounts: Vec<String> = amounts.iter().map(|amount| amount.to_string()).collect();
+        let amounts: Vec<&str> = amounts.iter().map(|amount| amount.as_str()).collect();
+
         MtTransfer {
             old_owner_id: owner_id,
             new_owner_id: receiver_id,
-            token_ids: &[token_id],
-            amounts: &[&amount.to_string()],
-            authorized_id: sender_id.filter(|sender_id| *sender_id == owner_id),
+            token_ids: &token_ids,
+            amounts: &amounts,
+            authorized_id: sender_id.filter(|sender_id| *sender_id != owner_id),
             memo: memo.as_deref(),
         }
         .emit();
@@ -411,71 +949,244 @@ impl MultiTokenCore for MultiToken {
         &mut self,
         receiver_id: AccountId,
         token_id: TokenId,
-        amount: Balance,
+        amount: U128,
         approval: Option<u64>,
     ) {
-        assert_one_yocto();
+        self.assert_transfer_deposit();
+        let initial_storage_usage = env::storage_usage();
         let sender_id = env::predecessor_account_id();
-        env::log_str(format!("Predecessor {}", sender_id).as_str());
-        self.internal_transfer(&sender_id, &receiver_id, &token_id, approval, amount);
+        let old_approvals = self.internal_transfer(
+            &sender_id,
+            &sender_id,
+            &receiver_id,
+            &token_id,
+            approval,
+            amount.into(),
+            None,
+        );
+        self.internal_refund_sender_deposit(initial_storage_usage);
+        self.internal_remove_spent_approvals(&token_id, &sender_id, old_approvals);
     }
 
     fn transfer_call(
         &mut self,
         receiver_id: AccountId,
         token_id: TokenId,
-        amount: Balance,
+        amount: U128,
         approval_id: Option<u64>,
         msg: String,
-    ) -> PromiseOrValue<bool> {
-        assert_one_yocto();
+    ) -> PromiseOrValue<U128> {
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer_call(
+            sender_id.clone(),
+            sender_id,
+            receiver_id,
+            token_id,
+            amount,
+            approval_id,
+            None,
+            msg,
+        )
+        .into()
+    }
+
+    fn mt_transfer_from(
+        &mut self,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) {
+        self.assert_transfer_deposit();
+        let initial_storage_usage = env::storage_usage();
+        let sender_id = env::predecessor_account_id();
+        let old_approvals = self.internal_transfer(
+            &sender_id,
+            &owner_id,
+            &receiver_id,
+            &token_id,
+            approval_id,
+            amount.into(),
+            memo,
+        );
+        self.internal_refund_sender_deposit(initial_storage_usage);
+        self.internal_remove_spent_approvals(&token_id, &owner_id, old_approvals);
+    }
+
+    fn mt_transfer_from_call(
+        &mut self,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        self.internal_transfer_call(
+            env::predecessor_account_id(),
+            owner_id,
+            receiver_id,
+            token_id,
+            amount,
+            approval_id,
+            memo,
+            msg,
+        )
+        .into()
+    }
+
+    fn mt_batch_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+    ) {
+        self.assert_transfer_deposit();
+        let initial_storage_usage = env::storage_usage();
+        let sender_id = env::predecessor_account_id();
+        let amounts: Vec<Balance> = amounts.into_iter().map(|amount| amount.into()).collect();
+        // Sender moves own tokens, so no approvals are used
+        self.internal_batch_transfer(
+            &sender_id,
+            &sender_id,
+            &receiver_id,
+            &token_ids,
+            &amounts,
+            None,
+            None,
+        );
+        self.internal_refund_sender_deposit(initial_storage_usage);
+    }
+
+    fn mt_batch_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>> {
+        self.assert_transfer_deposit();
+
+        // Resolving refunds every token of the batch, so it needs more gas for bigger batches
+        let extra_resolve_gas = Gas(GAS_FOR_RESOLVE_TRANSFER_PER_TOKEN.0 * token_ids.len() as u64);
+        let resolve_gas = GAS_FOR_RESOLVE_TRANSFER + extra_resolve_gas;
         require!(
-            env::prepaid_gas() > GAS_FOR_MT_TRANSFER_CALL + GAS_FOR_RESOLVE_TRANSFER,
+            env::prepaid_gas() > GAS_FOR_MT_TRANSFER_CALL + resolve_gas,
             "GAS!GAS!GAS! I gonna to step on the gas"
         );
+        let initial_storage_usage = env::storage_usage();
         let sender_id = env::predecessor_account_id();
+        let raw_amounts: Vec<Balance> = amounts.iter().map(|amount| amount.0).collect();
+
+        let old_approvals = self.internal_batch_transfer(
+            &sender_id,
+            &sender_id,
+            &receiver_id,
+            &token_ids,
+            &raw_amounts,
+            None,
+            None,
+        );
+        self.internal_refund_sender_deposit(initial_storage_usage);
 
-        let (old_owner, old_approvals) =
-            self.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, amount);
-
-        ext_receiver::on_transfer(
-            sender_id,
-            old_owner.clone(),
-            token_id.clone(),
-            amount,
+        ext_receiver::on_batch_transfer(
+            sender_id.clone(),
+            sender_id.clone(),
+            token_ids.clone(),
+            amounts.clone(),
             msg,
             receiver_id.clone(),
             NO_DEPOSIT,
-            env::prepaid_gas() - GAS_FOR_MT_TRANSFER_CALL,
+            env::prepaid_gas() - GAS_FOR_MT_TRANSFER_CALL - extra_resolve_gas,
         )
-        .then(ext_self::resolve_transfer(
-            old_owner,
+        .then(ext_self::resolve_batch_transfer(
+            sender_id,
             receiver_id,
-            token_id,
+            token_ids,
+            amounts,
             old_approvals,
             env::current_account_id(),
             NO_DEPOSIT,
-            GAS_FOR_RESOLVE_TRANSFER,
+            resolve_gas,
         ))
         .into()
     }
 
-    fn approval_for_all(&mut self, owner: AccountId, approved: bool) {
-        todo!()
+    fn approval_for_all(&mut self, operator_id: AccountId, approved: bool) {
+        let owner_id = env::predecessor_account_id();
+
+        // Deposit protects from granting operators with a function call access key
+        if approved {
+            require!(
+                env::attached_deposit() >= 1,
+                "Requires attached deposit of at least 1 yoctoNEAR"
+            );
+        } else {
+            assert_one_yocto();
+        }
+
+        let storage_diff = self.internal_approval_for_all(&owner_id, &operator_id, approved);
+
+        if approved {
+            self.internal_pay_storage(&owner_id, storage_diff.max(0) as u64);
+        } else if storage_diff < 0 {
+            self.internal_release_storage(&owner_id, (-storage_diff) as u64);
+        }
+    }
+
+    fn is_approved_for_all(&self, owner_id: AccountId, operator_id: AccountId) -> bool {
+        self.internal_is_approved_for_all(&owner_id, &operator_id)
     }
 
-    fn balance_of(&self, owner: AccountId, id: Vec<TokenId>) -> Vec<u128> {
-        self.balances_per_token
-            .iter()
-            .filter(|(token_id, _)| id.contains(token_id))
-            .map(|(_, balances)| {
-                balances
+    fn mt_burn(&mut self, token_id: TokenId, amount: U128, memo: Option<String>) {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        self.internal_burn(&owner_id, &token_id, amount.into(), None, memo);
+    }
+
+    fn mt_burn_from(
+        &mut self,
+        owner_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        require!(amount.0 > 0, "Amount to burn must be positive");
+        require!(self.owner_by_id.contains_key(&token_id), "Token not found");
+
+        let sender_id = env::predecessor_account_id();
+        let old_approvals =
+            self.internal_use_approval(&sender_id, &owner_id, &token_id, approval_id, amount.into());
+        self.internal_burn(&owner_id, &token_id, amount.into(), Some(&sender_id), memo);
+        self.internal_remove_spent_approvals(&token_id, &owner_id, old_approvals);
+    }
+
+    fn balance_of(&self, owner: AccountId, id: Vec<TokenId>) -> Vec<U128> {
+        id.iter()
+            .map(|token_id| {
+                self.balances_per_token
+                    .get(token_id)
+                    .expect("This token does not exist")
                     .get(&owner)
-                    .expect("User does not have account in of the tokens")
+                    .unwrap_or(0)
+                    .into()
             })
             .collect()
     }
 
+    fn mt_supply(&self, token_id: TokenId) -> Option<U128> {
+        self.total_supply.get(&token_id).map(U128::from)
+    }
+
+    fn mt_batch_supply(&self, token_ids: Vec<TokenId>) -> Vec<Option<U128>> {
+        token_ids.into_iter().map(|token_id| self.mt_supply(token_id)).collect()
+    }
+
     fn token(&self, token_id: TokenId) -> Option<Token> {
         let metadata = if let Some(metadata_by_id) = &self.token_metadata_by_id {
             metadata_by_id.get(&token_id)
@@ -485,16 +1196,12 @@ impl MultiTokenCore for MultiToken {
         let next_approval_id = self.next_approval_id_by_id.as_ref().unwrap().get(&token_id);
         let supply = self.total_supply.get(&token_id)?;
         let owner_id = self.owner_by_id.get(&token_id)?;
-        let approved_accounts = self
-            .approvals_by_id
-            .as_ref()
-            .and_then(|by_id| by_id.get(&token_id).or_else(|| Some(HashMap::new())));
-        let balances = self.balances_per_token.get(&token_id)?;
+        let approved_accounts = self.internal_approvals_of(&token_id, &owner_id);
 
         Some(Token {
             token_id,
             owner_id,
-            supply,
+            supply: supply.into(),
             balances: HashMap::new(),
             metadata,
             approvals: approved_accounts,
@@ -504,12 +1211,41 @@ impl MultiTokenCore for MultiToken {
 }
 
 impl MultiToken {
+    /// Approvals granted by `owner_id` for the token, `None` if approval management is not used
+    pub fn internal_approvals_of(
+        &self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+    ) -> Option<HashMap<AccountId, Approval>> {
+        self.approvals_by_id.as_ref()?;
+
+        let approved_accounts = match self.internal_approved_accounts(token_id, owner_id) {
+            Some(approved_accounts) => approved_accounts,
+            None => return Some(HashMap::new()),
+        };
+
+        Some(
+            approved_accounts
+                .iter()
+                .filter_map(|account_id| {
+                    let approval = self.internal_get_approval(token_id, owner_id, &account_id)?;
+                    Some((account_id, approval))
+                })
+                .collect(),
+        )
+    }
+
+    /// Resolves `transfer_call`. If the promise failed, all tokens are refunded to the sender.
+    /// If all tokens are refunded, the original approvals are restored.
+    ///
+    /// Returns the used amount and the amount burned because the sender's account was deleted.
     pub fn internal_resolve_transfer(
         &mut self,
         sender_id: &AccountId,
         receiver: AccountId,
         token_id: TokenId,
         amount: U128,
+        approvals: Option<HashMap<AccountId, Approval>>,
     ) -> (Balance, Balance) {
         let amount: Balance = amount.into();
 
@@ -522,30 +1258,176 @@ impl MultiToken {
                     amount
                 }
             }
-            PromiseResult::Failed => 0,
+            PromiseResult::Failed => amount,
+        };
+
+        self.internal_refund_unused(sender_id, &receiver, &token_id, amount, unused, approvals)
+    }
+
+    /// Resolves every token of a batch `transfer_call`. The receiver is expected to return
+    /// unused amounts in the same order as `token_ids`. If the promise failed or returned
+    /// a malformed result, all tokens are refunded to the sender. Approvals of every token
+    /// that was refunded completely are restored.
+    ///
+    /// Returns the used amount for each token.
+    pub fn internal_resolve_batch_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Vec<Option<HashMap<AccountId, Approval>>>,
+    ) -> Vec<Balance> {
+        let amounts: Vec<Balance> = amounts.into_iter().map(|amount| amount.into()).collect();
+
+        let unused: Vec<Balance> = match env::promise_result(0) {
+            PromiseResult::NotReady => env::abort(),
+            PromiseResult::Successful(value) => {
+                match near_sdk::serde_json::from_slice::<Vec<U128>>(&value) {
+                    Ok(unused) if unused.len() == amounts.len() => unused
+                        .iter()
+                        .zip(amounts.iter())
+                        .map(|(unused, amount)| std::cmp::min(*amount, unused.0))
+                        .collect(),
+                    _ => amounts.clone(),
+                }
+            }
+            PromiseResult::Failed => amounts.clone(),
         };
 
+        let approvals = approvals.into_iter().chain(std::iter::repeat(None));
+
+        token_ids
+            .iter()
+            .zip(amounts)
+            .zip(unused)
+            .zip(approvals)
+            .map(|(((token_id, amount), unused), approvals)| {
+                self.internal_refund_unused(sender_id, &receiver, token_id, amount, unused, approvals)
+                    .0
+            })
+            .collect()
+    }
+
+    /// Moves `unused` tokens from the receiver back to the sender and emits the refund event.
+    /// If everything is refunded, `approvals` are restored for the token, otherwise spent
+    /// approvals are removed.
+    ///
+    /// Returns the used amount and the amount burned because the sender's account was deleted.
+    fn internal_refund_unused(
+        &mut self,
+        sender_id: &AccountId,
+        receiver: &AccountId,
+        token_id: &TokenId,
+        amount: Balance,
+        unused: Balance,
+        approvals: Option<HashMap<AccountId, Approval>>,
+    ) -> (Balance, Balance) {
+        let mut refunded = 0;
+        let mut burned = 0;
+
         // All this `.get()` will not fail since it would fail before it gets to this call
         if unused > 0 {
-            let mut balances = self.balances_per_token.get(&token_id).unwrap();
-            let receiver_balance = balances.get(&receiver).unwrap_or(0);
+            let mut balances = self.balances_per_token.get(token_id).unwrap();
+            let receiver_balance = balances.get(receiver).unwrap_or(0);
 
             if receiver_balance > 0 {
                 let refund = std::cmp::min(receiver_balance, unused);
-                balances.insert(&receiver, &(receiver_balance - refund));
-
-                return if let Some(sender_balance) = balances.get(sender_id) {
+                balances.insert(receiver, &(receiver_balance - refund));
+                self.internal_update_token_of_owner(
+                    token_id,
+                    receiver,
+                    receiver_balance,
+                    receiver_balance - refund,
+                );
+
+                if let Some(sender_balance) = balances.get(sender_id) {
                     balances.insert(sender_id, &(sender_balance + refund));
+                    self.internal_update_token_of_owner(
+                        token_id,
+                        sender_id,
+                        sender_balance,
+                        sender_balance + refund,
+                    );
                     log!("Refund {} from {} to {}", refund, receiver, sender_id);
-                    (amount - refund, 0)
+                    MultiToken::emit_transfer(
+                        receiver,
+                        sender_id,
+                        std::slice::from_ref(token_id),
+                        &[refund],
+                        None,
+                        None,
+                    );
+                    refunded = refund;
                 } else {
-                    *self.total_supply.get(&token_id).as_mut().unwrap() -= refund;
+                    self.internal_decrease_supply(token_id, refund);
                     log!("The account of the sender was deleted");
-                    (amount, refund)
-                };
+                    MtBurn {
+                        owner_id: receiver,
+                        token_ids: &[token_id],
+                        amounts: &[&refund.to_string()],
+                        authorized_id: None,
+                        memo: None,
+                    }
+                    .emit();
+                    burned = refund;
+                }
+            }
+        }
+
+        if refunded == amount {
+            self.internal_restore_approvals(token_id, sender_id, approvals, amount);
+        } else {
+            self.internal_remove_spent_approvals(token_id, sender_id, approvals);
+        }
+
+        if burned > 0 {
+            (amount, burned)
+        } else {
+            (amount - refunded, 0)
+        }
+    }
+
+    /// Give back `amount` spent by a transfer that was reverted to the approvals it used. Only
+    /// approvals that still exist with the same approval ID are restored, so approvals revoked
+    /// or granted again while the transfer was in progress are left as they are.
+    fn internal_restore_approvals(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        approvals: Option<HashMap<AccountId, Approval>>,
+        amount: Balance,
+    ) {
+        if let (Some(_), Some(approvals)) = (self.approvals_by_id.as_ref(), approvals) {
+            for (account_id, approval) in approvals {
+                match self.internal_get_approval(token_id, owner_id, &account_id) {
+                    Some(mut current) if current.approval_id == approval.approval_id => {
+                        current.amount = current.amount.0.saturating_add(amount).into();
+                        self.internal_put_approval(token_id, owner_id, &account_id, &current);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Remove approvals that were spent completely by a finished transfer and give back
+    /// their storage to the owner. `approvals` are the ones returned by the transfer.
+    pub fn internal_remove_spent_approvals(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        approvals: Option<HashMap<AccountId, Approval>>,
+    ) {
+        for (account_id, approval) in approvals.unwrap_or_default() {
+            let is_spent = matches!(
+                self.internal_get_approval(token_id, owner_id, &account_id),
+                Some(current) if current.approval_id == approval.approval_id && current.amount.0 == 0
+            );
+            if is_spent {
+                self.internal_remove_approval(token_id, owner_id, &account_id);
             }
         }
-        (amount, 0)
     }
 }
 
@@ -556,9 +1438,24 @@ impl MultiTokenResolver for MultiToken {
         receiver: AccountId,
         token_id: TokenId,
         amount: U128,
+        approvals: Option<HashMap<AccountId, Approval>>,
     ) -> U128 {
-        self.internal_resolve_transfer(&sender_id, receiver, token_id, amount)
+        self.internal_resolve_transfer(&sender_id, receiver, token_id, amount, approvals)
             .0
             .into()
     }
+
+    fn resolve_batch_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Vec<Option<HashMap<AccountId, Approval>>>,
+    ) -> Vec<U128> {
+        self.internal_resolve_batch_transfer(&sender_id, receiver, token_ids, amounts, approvals)
+            .into_iter()
+            .map(U128::from)
+            .collect()
+    }
 }
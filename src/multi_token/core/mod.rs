@@ -12,7 +12,8 @@ pub use self::receiver::*;
 pub use self::resolver::*;
 
 use crate::multi_token::token::TokenId;
-use near_sdk::{AccountId, Balance, PromiseOrValue};
+use near_sdk::json_types::U128;
+use near_sdk::{AccountId, PromiseOrValue};
 
 use super::token::Token;
 
@@ -33,7 +34,7 @@ pub trait MultiTokenCore {
         &mut self,
         receiver_id: AccountId,
         token_id: TokenId,
-        amount: Balance,
+        amount: U128,
         approval: Option<u64>,
     );
 
@@ -50,18 +51,150 @@ pub trait MultiTokenCore {
     /// * `memo`: Used as context
     /// * `msg`: Additional msg that will be passed to receiving contract
     ///
-    /// returns: PromiseOrValue<bool>
+    /// returns: PromiseOrValue<U128> with the used amount
     ///
     fn transfer_call(
         &mut self,
         receiver_id: AccountId,
         token_id: TokenId,
-        amount: Balance,
+        amount: U128,
         approval_id: Option<u64>,
         msg: String,
-    ) -> PromiseOrValue<bool>;
+    ) -> PromiseOrValue<U128>;
 
-    fn approval_for_all(&mut self, owner: AccountId, approved: bool);
+    /// Transfer tokens of another account. Caller must be an operator of the owner or have an
+    /// approval for the token, in the latter case the approved amount is decreased.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner_id`: Account whose tokens are sent
+    /// * `receiver_id`: Receiver of tokens
+    /// * `token_id`: ID of token to send
+    /// * `amount`: How much to send
+    /// * `approval_id`: ID of approval for caller
+    /// * `memo`: Used as context
+    ///
+    /// returns: ()
+    ///
+    fn mt_transfer_from(
+        &mut self,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    );
+
+    /// Same as `mt_transfer_from`, but calls `on_transfer` on receiver contract like `transfer_call`.
+    /// Unused tokens are refunded to `owner_id` and the used approval is restored if everything is refunded.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner_id`: Account whose tokens are sent
+    /// * `receiver_id`: NEAR account receiving MT
+    /// * `token_id`: Token to send
+    /// * `amount`: How much to send
+    /// * `approval_id`: ID of approval for caller
+    /// * `memo`: Used as context
+    /// * `msg`: Additional msg that will be passed to receiving contract
+    ///
+    /// returns: PromiseOrValue<U128> with the used amount
+    ///
+    fn mt_transfer_from_call(
+        &mut self,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128>;
+
+    /// Make a batch transfer of several tokens of the caller to the same receiver
+    ///
+    /// # Arguments
+    ///
+    /// * `receiver_id`: Receiver of tokens
+    /// * `token_ids`: IDs of tokens to send
+    /// * `amounts`: How much to send of each token, must have the same length as `token_ids`
+    ///
+    /// returns: ()
+    ///
+    fn mt_batch_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+    );
+
+    /// Batch transfer of MTs with a single call of `on_batch_transfer` on receiver contract.
+    /// A successful workflow will end in a success execution outcome to the callback on the MT
+    /// contract at the method `resolve_batch_transfer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `receiver_id`: NEAR account receiving MTs
+    /// * `token_ids`: Tokens to send
+    /// * `amounts`: How much to send of each token, must have the same length as `token_ids`
+    /// * `msg`: Additional msg that will be passed to receiving contract
+    ///
+    /// returns: PromiseOrValue<Vec<U128>> with the used amount of each token
+    ///
+    fn mt_batch_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>>;
+
+    /// Grant or revoke a permission for an operator to transfer all tokens of the caller.
+    /// Granting requires at least 1 yoctoNEAR, storage is taken from the caller's storage balance
+    /// or from the deposit, the rest is refunded. Revoking requires exactly 1 yoctoNEAR and gives back the released storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator_id`: Account to approve or revoke
+    /// * `approved`: `true` to grant the approval, `false` to revoke it
+    fn approval_for_all(&mut self, operator_id: AccountId, approved: bool);
+
+    /// Check if operator is approved to transfer all tokens of owner
+    ///
+    /// # Arguments
+    ///
+    /// * `owner_id`: Owner of tokens
+    /// * `operator_id`: Account to check
+    fn is_approved_for_all(&self, owner_id: AccountId, operator_id: AccountId) -> bool;
+
+    /// Burn tokens from the balance of the caller. Requires exactly 1 yoctoNEAR.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_id`: Token to burn
+    /// * `amount`: How much to burn
+    /// * `memo`: Used as context
+    fn mt_burn(&mut self, token_id: TokenId, amount: U128, memo: Option<String>);
+
+    /// Burn tokens of `owner_id` by an operator or an approved account of the owner, the approved
+    /// amount is decreased by `amount`. Requires exactly 1 yoctoNEAR.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner_id`: Owner of tokens
+    /// * `token_id`: Token to burn
+    /// * `amount`: How much to burn
+    /// * `approval_id`: Expected approval ID, to make sure the approval wasn't changed
+    /// * `memo`: Used as context
+    fn mt_burn_from(
+        &mut self,
+        owner_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    );
 
     /// Get balance of user in specified tokens
     ///
@@ -69,8 +202,22 @@ pub trait MultiTokenCore {
     /// 
     /// * `owner`: Account to check
     /// # `id`: Vector of token IDs
-    fn balance_of(&self, owner: AccountId, id: Vec<TokenId>) -> Vec<u128>;
+    fn balance_of(&self, owner: AccountId, id: Vec<TokenId>) -> Vec<U128>;
+
+
+    /// Get total supply of token, `None` if token does not exist
+    ///
+    /// # Arguments
+    ///
+    /// * `token_id`: Token to check
+    fn mt_supply(&self, token_id: TokenId) -> Option<U128>;
 
+    /// Get total supply of several tokens, `None` for every token that does not exist
+    ///
+    /// # Arguments
+    ///
+    /// * `token_ids`: Vector of token IDs
+    fn mt_batch_supply(&self, token_ids: Vec<TokenId>) -> Vec<Option<U128>>;
 
     /// Get all possible info about token
     fn token(&self, token_id: TokenId) -> Option<Token>;
@@ -34,4 +34,27 @@ pub trait MultiTokenReceiver {
         amounts: U128,
         msg: String,
     ) -> PromiseOrValue<U128>;
+
+    /// Take some action after receiving a batch of multi-tokens from `mt_batch_transfer_call`
+    ///
+    /// ## Requirements:
+    /// * Same as for `on_transfer`
+    ///
+    /// ## Arguments:
+    /// * `sender_id`: the sender of `mt_batch_transfer_call`
+    /// * `previous_owner_id`: the account that owned the tokens prior to the transfer
+    /// * `token_ids`: the `token_ids` argument given to `mt_batch_transfer_call`
+    /// * `amounts`: the `amounts` argument given to `mt_batch_transfer_call`
+    /// * `msg`: information necessary for this contract to know how to process the
+    ///   request. This may include method names and/or arguments.
+    ///
+    /// Returns the number of unused tokens for each of `token_ids`, in the same order.
+    fn on_batch_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>>;
 }
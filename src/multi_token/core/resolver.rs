@@ -1,6 +1,7 @@
-use crate::multi_token::token::TokenId;
+use crate::multi_token::token::{Approval, TokenId};
 use near_sdk::json_types::U128;
 use near_sdk::AccountId;
+use std::collections::HashMap;
 
 /// `resolve_transfer` will be called after `on_transfer`
 pub trait MultiTokenResolver {
@@ -11,8 +12,8 @@ pub trait MultiTokenResolver {
     /// 1. Sender calls `transfer_call` on MT contract
     /// 2. MT contract transfers tokens from sender to receiver
     /// 3. MT contract calls `on_transfer` on receiver contract
-    /// 4+. [receiver may make cross-contract calls]
-    /// N. MT contract resolves chain with `resolve_transfer` and may do anything
+    /// 4. [receiver may make cross-contract calls]
+    /// 5. MT contract resolves chain with `resolve_transfer` and may do anything
     ///
     /// Requirements:
     /// * Contract MUST forbid calls to this function by any account except self
@@ -24,6 +25,7 @@ pub trait MultiTokenResolver {
     /// * `previous_owner_id`: the owner prior to the call to `transfer_call`
     /// * `receiver_id`: the `receiver_id` argument given to `transfer_call`
     /// * `token_ids`: the vector of `token_id` argument given to `transfer_call`
+    /// * `amount`: the `amount` argument given to `transfer_call`
     /// * `approvals`: if using Approval Management, contract MUST provide
     ///   set of original approved accounts in this argument, and restore these
     ///   approved accounts in case of revert.
@@ -34,12 +36,29 @@ pub trait MultiTokenResolver {
     /// Example: if sender_id calls `transfer_call({ "amounts": ["100"], token_ids: ["55"], receiver_id: "games" })`,
     /// but `receiver_id` only uses 80, `on_transfer` will resolve with `["20"]`, and `resolve_transfer`
     /// will return `[80]`.
-
     fn resolve_transfer(
         &mut self,
         sender_id: AccountId,
         receiver: AccountId,
         token_id: TokenId,
         amount: U128,
+        approvals: Option<HashMap<AccountId, Approval>>,
     ) -> U128;
+
+    /// Finalizes chain of cross-contract calls that started from `mt_batch_transfer_call`
+    ///
+    /// Works the same way as `resolve_transfer`, but for every token of the batch.
+    /// `on_batch_transfer` must resolve with unused amounts in the same order as `token_ids`,
+    /// otherwise the whole batch is returned to `sender_id`. `approvals` holds the original
+    /// approvals of every token, in the same order as `token_ids`.
+    ///
+    /// Returns the used amount of each token.
+    fn resolve_batch_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Vec<Option<HashMap<AccountId, Approval>>>,
+    ) -> Vec<U128>;
 }
@@ -1,18 +1,16 @@
 use std::collections::HashMap;
 
-use near_sdk::{AccountId, require};
+use near_sdk::{json_types::U128, AccountId, require};
 
-use crate::multi_token::{core::MultiToken, token::{Token, TokenId}};
+use crate::multi_token::{core::MultiToken, metadata::TokenMetadata, token::{Token, TokenId}};
 
-use super::MultiTokenEnumeration;
+use super::{MultiTokenEnumeration, MultiTokenEnumerationMetadata};
 
 impl MultiToken {
     fn enum_get_token(&self, owner_id: AccountId, token_id: TokenId) -> Token {
         let metadata = self.token_metadata_by_id.as_ref().unwrap().get(&token_id);
-        let approved_account_ids =
-            Some(self.approvals_by_id.as_ref().unwrap().get(&token_id).unwrap_or_default());
-        let supply = self.total_supply.get(&token_id).unwrap();
-        let approvals = self.approvals_by_id.as_ref().unwrap().get(&token_id);
+        let supply = self.total_supply.get(&token_id).unwrap().into();
+        let approvals = self.internal_approvals_of(&token_id, &owner_id);
         let next_approval_id = self.next_approval_id_by_id.as_ref().unwrap().get(&token_id);
 
         Token { token_id, owner_id, metadata, approvals, supply, balances: HashMap::new(), next_approval_id }
@@ -56,4 +54,59 @@ impl MultiTokenEnumeration for MultiToken {
         .map(|token_id| self.enum_get_token(account_id.clone(), token_id))
         .collect()
     }
-}
\ No newline at end of file
+
+    fn mt_holders(&self, token_id: TokenId, from_index: Option<u64>, limit: u64) -> Vec<(AccountId, U128)> {
+        let holders_per_token = self.holders_per_token.as_ref().expect("Could not find field");
+
+        let holders = if let Some(set) = holders_per_token.get(&token_id) {
+            set
+        } else {
+            return vec![];
+        };
+
+        require!(limit != 0, "Limit cannot be 0");
+        let from_index = from_index.unwrap_or(0);
+
+        require!(holders.len() > from_index, "Out of bounds");
+
+        let balances = self.balances_per_token.get(&token_id).unwrap();
+
+        holders
+        .iter()
+        .skip(from_index as usize)
+        .take(limit as usize)
+        .map(|account_id| {
+            let balance = balances.get(&account_id).unwrap_or(0);
+            (account_id, balance.into())
+        })
+        .collect()
+    }
+
+    fn mt_holder_count(&self, token_id: TokenId) -> u64 {
+        let holders_per_token = self.holders_per_token.as_ref().expect("Could not find field");
+
+        holders_per_token.get(&token_id).map(|set| set.len()).unwrap_or(0)
+    }
+}
+
+impl MultiTokenEnumerationMetadata for MultiToken {
+    fn token_metadata_all(&self, from_index: Option<u64>, limit: u64) -> Vec<(TokenId, TokenMetadata)> {
+        let token_metadata_by_id = self.token_metadata_by_id.as_ref().expect("Could not find field");
+
+        let from_index = from_index.unwrap_or(0);
+
+        require!(self.owner_by_id.len() > from_index, "Out of bounds");
+
+        require!(limit != 0, "Limit cannot be 0");
+
+        self.owner_by_id
+        .iter()
+        .skip(from_index as usize)
+        .take(limit as usize)
+        .filter_map(|(token_id, _)| {
+            let metadata = token_metadata_by_id.get(&token_id)?;
+            Some((token_id, metadata))
+        })
+        .collect()
+    }
+}
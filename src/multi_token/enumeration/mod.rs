@@ -1,9 +1,10 @@
+use near_sdk::json_types::U128;
 use near_sdk::AccountId;
 
 
 pub mod enumeration_impl;
 
-use super::{metadata::MtContractMetadata, token::Token};
+use super::{metadata::TokenMetadata, token::{Token, TokenId}};
 
 /// Enumeration extension for NEP-246
 /// See specs here -> <https://github.com/shipsgold/NEPs/blob/master/specs/Standards/MultiToken/Enumeration.md>
@@ -28,17 +29,31 @@ pub trait MultiTokenEnumeration {
     /// returns: List of [Token]s owner by user
     ///
     fn token_by_owner(&self, account_id: AccountId, from_index: Option<u64>, limit: u64) -> Vec<Token>;
+
+    /// Get list of holders of a given token with their balances, requires the holders index
+    /// of [`MultiToken`](crate::multi_token::core::MultiToken)
+    ///
+    /// # Arguments:
+    /// * `token_id`: ID of the token
+    /// * `from_index` - Index to start from, defaults to 0 if not provided
+    /// * `limit` - The maximum number of holders to return
+    ///
+    /// returns: List of account IDs with positive balance and their balances
+    ///
+    fn mt_holders(&self, token_id: TokenId, from_index: Option<u64>, limit: u64) -> Vec<(AccountId, U128)>;
+
+    /// Get number of accounts with positive balance of a given token, requires the holders index
+    fn mt_holder_count(&self, token_id: TokenId) -> u64;
 }
 
 /// The contract must implement the following view methods if using metadata extension
 pub trait MultiTokenEnumerationMetadata {
-    /// Get list of all base metadata for the contract
+    /// Get list of metadata of all tokens for the contract (with pagination)
     ///
     /// Arguments:
-    /// * `from_index`: a string representing an unsigned 128-bit integer,
-    ///    representing the starting index of tokens to return
+    /// * `from_index` - Index of token to start from, defaults to 0 if not provided
     /// * `limit`: the maximum number of tokens to return
     ///
-    /// Returns an array of `MTBaseTokenMetadata` objects, as described in the Metadata standard, and an empty array if there are no tokens
-    fn token_metadata_all(&self, from_index: Option<u64>, limit: u64) -> Vec<MtContractMetadata>;
+    /// Returns an array of token IDs with their `TokenMetadata`, tokens without metadata are skipped
+    fn token_metadata_all(&self, from_index: Option<u64>, limit: u64) -> Vec<(TokenId, TokenMetadata)>;
 }
@@ -49,7 +49,121 @@ impl MtTransfer<'_> {
     }
 }
 
-// TODO: Burn event
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct MtApprovalForAll<'a> {
+    pub owner_id: &'a AccountId,
+    pub operator_id: &'a AccountId,
+    pub approved: bool,
+}
+
+impl MtApprovalForAll<'_> {
+    pub fn emit(self) {
+        Self::emit_many(&[self])
+    }
+
+    pub fn emit_many(data: &[MtApprovalForAll<'_>]) {
+        new_246_v1(Nep246EventKind::MtApprovalForAll(data)).emit()
+    }
+}
+
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct MtBurn<'a> {
+    pub owner_id: &'a AccountId,
+    pub token_ids: &'a [&'a str],
+    pub amounts: &'a [&'a str],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<&'a AccountId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>
+}
+
+impl MtBurn<'_> {
+    pub fn emit(self) {
+        Self::emit_many(&[self])
+    }
+
+    pub fn emit_many(data: &[MtBurn<'_>]) {
+        new_246_v1(Nep246EventKind::MtBurn(data)).emit()
+    }
+}
+
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct MtApprove<'a> {
+    pub owner_id: &'a AccountId,
+    pub account_id: &'a AccountId,
+    pub token_ids: &'a [&'a str],
+    pub amounts: &'a [&'a str],
+    pub approval_ids: &'a [u64],
+}
+
+impl MtApprove<'_> {
+    pub fn emit(self) {
+        Self::emit_many(&[self])
+    }
+
+    pub fn emit_many(data: &[MtApprove<'_>]) {
+        new_246_v1(Nep246EventKind::MtApprove(data)).emit()
+    }
+}
+
+/// Emitted when an approved account spends a part of its approval with a transfer
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct MtApprovalUsed<'a> {
+    pub owner_id: &'a AccountId,
+    pub account_id: &'a AccountId,
+    pub token_ids: &'a [&'a str],
+    pub amounts: &'a [&'a str],
+    pub remaining_amounts: &'a [&'a str],
+}
+
+impl MtApprovalUsed<'_> {
+    pub fn emit(self) {
+        Self::emit_many(&[self])
+    }
+
+    pub fn emit_many(data: &[MtApprovalUsed<'_>]) {
+        new_246_v1(Nep246EventKind::MtApprovalUsed(data)).emit()
+    }
+}
+
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct MtRevoke<'a> {
+    pub owner_id: &'a AccountId,
+    pub account_id: &'a AccountId,
+    pub token_ids: &'a [&'a str],
+}
+
+impl MtRevoke<'_> {
+    pub fn emit(self) {
+        Self::emit_many(&[self])
+    }
+
+    pub fn emit_many(data: &[MtRevoke<'_>]) {
+        new_246_v1(Nep246EventKind::MtRevoke(data)).emit()
+    }
+}
+
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct MtRevokeAll<'a> {
+    pub owner_id: &'a AccountId,
+    pub token_ids: &'a [&'a str],
+}
+
+impl MtRevokeAll<'_> {
+    pub fn emit(self) {
+        Self::emit_many(&[self])
+    }
+
+    pub fn emit_many(data: &[MtRevokeAll<'_>]) {
+        new_246_v1(Nep246EventKind::MtRevokeAll(data)).emit()
+    }
+}
 
 #[derive(Serialize, Debug)]
 pub(crate) struct Nep246Event<'a> {
@@ -65,7 +179,12 @@ pub(crate) struct Nep246Event<'a> {
 enum Nep246EventKind<'a> {
     MtMint(&'a [MtMint<'a>]),
     MtTransfer(&'a [MtTransfer<'a>]),
-    // NftBurn(&'a [NftBurn<'a>]),
+    MtApprovalForAll(&'a [MtApprovalForAll<'a>]),
+    MtBurn(&'a [MtBurn<'a>]),
+    MtApprove(&'a [MtApprove<'a>]),
+    MtApprovalUsed(&'a [MtApprovalUsed<'a>]),
+    MtRevoke(&'a [MtRevoke<'a>]),
+    MtRevokeAll(&'a [MtRevokeAll<'a>]),
 }
 
 fn new_246<'a>(version: &'static str, event_kind: Nep246EventKind<'a>) -> NearEvent<'a> {
@@ -5,6 +5,7 @@ macro_rules! impl_multi_token_core {
     ($contract: ident, $token: ident) => {
         use $crate::multi_token::core::MultiTokenCore;
         use $crate::multi_token::core::MultiTokenResolver;
+        use $crate::multi_token::token::Approval;
 
         #[near_bindgen]
         impl MultiTokenCore for $contract {
@@ -13,7 +14,7 @@ macro_rules! impl_multi_token_core {
                 &mut self,
                 receiver_id: AccountId,
                 token_id: TokenId,
-                amount: Balance,
+                amount: U128,
                 approval: Option<u64>,
             ) {
                 self.$token.transfer(receiver_id, token_id, amount, approval)
@@ -24,22 +25,110 @@ macro_rules! impl_multi_token_core {
                 &mut self,
                 receiver_id: AccountId,
                 token_id: TokenId,
-                amount: Balance,
+                amount: U128,
                 approval_id: Option<u64>,
                 msg: String,
-            ) -> PromiseOrValue<bool> {
+            ) -> PromiseOrValue<U128> {
                 self.$token.transfer_call(receiver_id, token_id, amount, approval_id, msg)
             }
 
+            #[payable]
+            fn mt_transfer_from(
+                &mut self,
+                owner_id: AccountId,
+                receiver_id: AccountId,
+                token_id: TokenId,
+                amount: U128,
+                approval_id: Option<u64>,
+                memo: Option<String>,
+            ) {
+                self.$token.mt_transfer_from(owner_id, receiver_id, token_id, amount, approval_id, memo)
+            }
+
+            #[payable]
+            fn mt_transfer_from_call(
+                &mut self,
+                owner_id: AccountId,
+                receiver_id: AccountId,
+                token_id: TokenId,
+                amount: U128,
+                approval_id: Option<u64>,
+                memo: Option<String>,
+                msg: String,
+            ) -> PromiseOrValue<U128> {
+                self.$token.mt_transfer_from_call(
+                    owner_id,
+                    receiver_id,
+                    token_id,
+                    amount,
+                    approval_id,
+                    memo,
+                    msg,
+                )
+            }
+
+            #[payable]
+            fn mt_batch_transfer(
+                &mut self,
+                receiver_id: AccountId,
+                token_ids: Vec<TokenId>,
+                amounts: Vec<U128>,
+            ) {
+                self.$token.mt_batch_transfer(receiver_id, token_ids, amounts)
+            }
+
+            #[payable]
+            fn mt_batch_transfer_call(
+                &mut self,
+                receiver_id: AccountId,
+                token_ids: Vec<TokenId>,
+                amounts: Vec<U128>,
+                msg: String,
+            ) -> PromiseOrValue<Vec<U128>> {
+                self.$token.mt_batch_transfer_call(receiver_id, token_ids, amounts, msg)
+            }
+
+            fn mt_supply(&self, token_id: TokenId) -> Option<U128> {
+                self.$token.mt_supply(token_id)
+            }
+
+            fn mt_batch_supply(&self, token_ids: Vec<TokenId>) -> Vec<Option<U128>> {
+                self.$token.mt_batch_supply(token_ids)
+            }
+
             fn token(&self, token_id: TokenId) -> Option<Token> {
                 self.$token.token(token_id)
             }
             
-            fn balance_of(&self, owner: AccountId, id: Vec<TokenId>) -> Vec<u128> { 
+            fn balance_of(&self, owner: AccountId, id: Vec<TokenId>) -> Vec<U128> {
                 self.$token.balance_of(owner, id)
-             }
-            
-            fn approval_for_all(&mut self, owner_id: AccountId, approved: bool) { todo!() }
+            }
+
+            #[payable]
+            fn approval_for_all(&mut self, operator_id: AccountId, approved: bool) {
+                self.$token.approval_for_all(operator_id, approved)
+            }
+
+            fn is_approved_for_all(&self, owner_id: AccountId, operator_id: AccountId) -> bool {
+                self.$token.is_approved_for_all(owner_id, operator_id)
+            }
+
+            #[payable]
+            fn mt_burn(&mut self, token_id: TokenId, amount: U128, memo: Option<String>) {
+                self.$token.mt_burn(token_id, amount, memo)
+            }
+
+            #[payable]
+            fn mt_burn_from(
+                &mut self,
+                owner_id: AccountId,
+                token_id: TokenId,
+                amount: U128,
+                approval_id: Option<u64>,
+                memo: Option<String>,
+            ) {
+                self.$token.mt_burn_from(owner_id, token_id, amount, approval_id, memo)
+            }
         }
 
 
@@ -50,17 +139,31 @@ macro_rules! impl_multi_token_core {
             fn resolve_transfer(
                 &mut self,
                 sender_id: AccountId,
-                receiver_id: AccountId,
+                receiver: AccountId,
                 token_id: TokenId,
-                amount: U128
+                amount: U128,
+                approvals: Option<std::collections::HashMap<AccountId, Approval>>,
             ) -> U128 {
                 self.$token.resolve_transfer(
                     sender_id,
-                    receiver_id,
+                    receiver,
                     token_id,
-                    amount
+                    amount,
+                    approvals
                 )
             }
+
+            #[private]
+            fn resolve_batch_transfer(
+                &mut self,
+                sender_id: AccountId,
+                receiver: AccountId,
+                token_ids: Vec<TokenId>,
+                amounts: Vec<U128>,
+                approvals: Vec<Option<std::collections::HashMap<AccountId, Approval>>>,
+            ) -> Vec<U128> {
+                self.$token.resolve_batch_transfer(sender_id, receiver, token_ids, amounts, approvals)
+            }
         }
     };
 }
@@ -78,11 +181,12 @@ macro_rules! impl_multi_token_approval {
             fn approve(
                 &mut self,
                 account_id: AccountId,
-                token_id: TokenId,
-                amount: Balance,
+                token_ids: Vec<TokenId>,
+                amounts: Vec<U128>,
+                expires_at: Option<u64>,
                 msg: Option<String>,
             ) -> Option<Promise> {
-                self.$token.approve(account_id, token_id, amount, msg)
+                self.$token.approve(account_id, token_ids, amounts, expires_at, msg)
             }
 
             #[payable]
@@ -98,11 +202,59 @@ macro_rules! impl_multi_token_approval {
             fn is_approved(
                 &self,
                 token_id: TokenId,
+                owner_id: AccountId,
                 approved_account_id: AccountId,
-                amount: Balance,
+                amount: U128,
                 approval: Option<u64>,
             ) -> bool {
-                self.$token.is_approved(token_id, approved_account_id, amount, approval)
+                self.$token.is_approved(token_id, owner_id, approved_account_id, amount, approval)
+            }
+
+            fn mt_allowance(
+                &self,
+                owner_id: AccountId,
+                spender_id: AccountId,
+                token_id: TokenId,
+            ) -> U128 {
+                self.$token.mt_allowance(owner_id, spender_id, token_id)
+            }
+
+            #[payable]
+            fn increase_allowance(&mut self, account_id: AccountId, token_id: TokenId, amount: U128) {
+                self.$token.increase_allowance(account_id, token_id, amount)
+            }
+
+            #[payable]
+            fn decrease_allowance(&mut self, account_id: AccountId, token_id: TokenId, amount: U128) {
+                self.$token.decrease_allowance(account_id, token_id, amount)
+            }
+
+            fn prune_expired_approvals(
+                &mut self,
+                token_id: TokenId,
+                from_index: Option<u64>,
+                limit: u64,
+            ) -> u64 {
+                self.$token.prune_expired_approvals(token_id, from_index, limit)
+            }
+
+            fn mt_approvals(
+                &self,
+                token_id: TokenId,
+                owner_id: AccountId,
+                from_index: Option<u64>,
+                limit: u64,
+            ) -> Vec<$crate::multi_token::token::TokenApproval> {
+                self.$token.mt_approvals(token_id, owner_id, from_index, limit)
+            }
+
+            fn mt_approvals_by_spender(
+                &self,
+                spender_id: AccountId,
+                from_index: Option<u64>,
+                limit: u64,
+            ) -> Vec<$crate::multi_token::token::TokenApproval> {
+                self.$token.mt_approvals_by_spender(spender_id, from_index, limit)
             }
         }
     };
@@ -124,6 +276,119 @@ macro_rules! impl_multi_token_enumeration {
             fn token_by_owner(&self, account_id: AccountId, from_index: Option<u64>, limit: u64) -> Vec<Token> {
                 self.$token.token_by_owner(account_id, from_index, limit)
             }
+
+            fn mt_holders(&self, token_id: TokenId, from_index: Option<u64>, limit: u64) -> Vec<(AccountId, U128)> {
+                self.$token.mt_holders(token_id, from_index, limit)
+            }
+
+            fn mt_holder_count(&self, token_id: TokenId) -> u64 {
+                self.$token.mt_holder_count(token_id)
+            }
+        }
+    };
+}
+
+/// Metadata views of the contract and its tokens. Contract metadata is read from
+/// `$metadata`, a `LazyOption<MtContractMetadata>` field of the contract.
+#[macro_export]
+macro_rules! impl_multi_token_metadata {
+    ($contract: ident, $token: ident, $metadata: ident) => {
+        use $crate::multi_token::metadata::MultiTokenMetadataProvider;
+
+        #[near_bindgen]
+        impl MultiTokenMetadataProvider for $contract {
+            fn mt_metadata(&self) -> $crate::multi_token::metadata::MtContractMetadata {
+                self.$metadata.get().unwrap()
+            }
+
+            fn mt_metadata_token_all(
+                &self,
+                token_ids: Vec<TokenId>,
+            ) -> Vec<Option<$crate::multi_token::metadata::MtTokenMetadataAll>> {
+                self.$token.mt_metadata_token_all(token_ids)
+            }
+
+            fn mt_metadata_token_by_token_id(
+                &self,
+                token_id: TokenId,
+            ) -> Option<$crate::multi_token::metadata::TokenMetadata> {
+                self.$token.mt_metadata_token_by_token_id(token_id)
+            }
+
+            fn mt_metadata_base_by_token_id(
+                &self,
+                token_id: TokenId,
+            ) -> Option<$crate::multi_token::metadata::MtBaseTokenMetadata> {
+                self.$token.mt_metadata_base_by_token_id(token_id)
+            }
+
+            fn mt_metadata_base_by_metadata_id(
+                &self,
+                base_metadata_id: String,
+            ) -> Option<$crate::multi_token::metadata::MtBaseTokenMetadata> {
+                self.$token.mt_metadata_base_by_metadata_id(base_metadata_id)
+            }
+        }
+    };
+}
+
+/// Metadata enumeration adds a view-only method to get metadata of all tokens.
+/// Requires token metadata to be stored by `MultiToken`.
+#[macro_export]
+macro_rules! impl_multi_token_enumeration_metadata {
+    ($contract: ident, $token: ident) => {
+        use $crate::multi_token::enumeration::MultiTokenEnumerationMetadata;
+
+        #[near_bindgen]
+        impl MultiTokenEnumerationMetadata for $contract {
+            fn token_metadata_all(
+                &self,
+                from_index: Option<u64>,
+                limit: u64,
+            ) -> Vec<(TokenId, $crate::multi_token::metadata::TokenMetadata)> {
+                self.$token.token_metadata_all(from_index, limit)
+            }
+        }
+    };
+}
+
+/// Storage management according to NEP-145, allows accounts to prepay storage
+/// used for registrations and approvals.
+#[macro_export]
+macro_rules! impl_multi_token_storage {
+    ($contract: ident, $token: ident) => {
+        use $crate::multi_token::storage_management::{
+            StorageBalance, StorageBalanceBounds, StorageManagement,
+        };
+
+        #[near_bindgen]
+        impl StorageManagement for $contract {
+            #[payable]
+            fn storage_deposit(
+                &mut self,
+                account_id: Option<AccountId>,
+                registration_only: Option<bool>,
+            ) -> StorageBalance {
+                self.$token.storage_deposit(account_id, registration_only)
+            }
+
+            #[payable]
+            fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+                self.$token.storage_withdraw(amount)
+            }
+
+            #[payable]
+            fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+                self.$token.storage_unregister(force)
+            }
+
+            fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+                self.$token.storage_balance_bounds()
+            }
+
+            fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+                self.$token.storage_balance_of(account_id)
+            }
         }
     };
 }
\ No newline at end of file
@@ -2,6 +2,8 @@ use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::require;
 use near_sdk::serde::{Deserialize, Serialize};
 
+use crate::multi_token::{core::MultiToken, token::TokenId};
+
 /// Version of standard
 pub const MT_METADATA_SPEC: &str = "mt-0.0.1";
 
@@ -18,6 +20,36 @@ pub struct MtContractMetadata {
     pub reference_hash: Option<String>,
 }
 
+/// Metadata shared by a series of tokens, tokens are linked to it by `id`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MtBaseTokenMetadata {
+    /// ID of the base metadata
+    pub id: String,
+    pub name: String,
+    pub symbol: Option<String>,
+    /// Data URL
+    pub icon: Option<String>,
+    /// Number of decimals of fungible tokens of the series
+    pub decimals: Option<String>,
+    /// Centralized gateway known to have reliable access to decentralized storage assets referenced by `reference` or `media` URLs
+    pub base_uri: Option<String>,
+    /// URL to an off-chain JSON file with more info.
+    pub reference: Option<String>,
+    /// Number of copies of the series
+    pub copies: Option<u64>,
+    /// Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
+    pub reference_hash: Option<String>,
+}
+
+/// Base metadata of a token merged with its own metadata
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MtTokenMetadataAll {
+    pub base: Option<MtBaseTokenMetadata>,
+    pub token: Option<TokenMetadata>,
+}
+
 /// Metadata for each token
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(crate = "near_sdk::serde")]
@@ -45,9 +77,87 @@ pub struct TokenMetadata {
     pub reference_hash: Option<String>,
 }
 
-/// Offers details on the contract-level metadata.
+/// Offers details on the contract-level metadata and metadata of tokens.
 pub trait MultiTokenMetadataProvider {
     fn mt_metadata(&self) -> MtContractMetadata;
+
+    /// Get metadata of tokens merged with their base metadata in the same order as `token_ids`,
+    /// `None` for tokens without both metadata and base metadata
+    fn mt_metadata_token_all(&self, token_ids: Vec<TokenId>) -> Vec<Option<MtTokenMetadataAll>>;
+
+    /// Get metadata of a token, `None` if token doesn't have metadata
+    fn mt_metadata_token_by_token_id(&self, token_id: TokenId) -> Option<TokenMetadata>;
+
+    /// Get base metadata the token is linked to, `None` if token isn't linked to any
+    fn mt_metadata_base_by_token_id(&self, token_id: TokenId) -> Option<MtBaseTokenMetadata>;
+
+    /// Get base metadata by its ID
+    fn mt_metadata_base_by_metadata_id(&self, base_metadata_id: String) -> Option<MtBaseTokenMetadata>;
+}
+
+impl MultiToken {
+    /// Metadata of tokens merged with their base metadata in the same order as `token_ids`,
+    /// `None` for tokens without both metadata and base metadata
+    pub fn mt_metadata_token_all(&self, token_ids: Vec<TokenId>) -> Vec<Option<MtTokenMetadataAll>> {
+        token_ids
+            .into_iter()
+            .map(|token_id| {
+                let token = self.mt_metadata_token_by_token_id(token_id.clone());
+                let base = self.mt_metadata_base_by_token_id(token_id);
+                if token.is_none() && base.is_none() {
+                    return None;
+                }
+                Some(MtTokenMetadataAll { base, token })
+            })
+            .collect()
+    }
+
+    /// Metadata of a token, `None` if token doesn't have metadata
+    pub fn mt_metadata_token_by_token_id(&self, token_id: TokenId) -> Option<TokenMetadata> {
+        self.token_metadata_by_id
+            .as_ref()
+            .expect("Token metadata is not supported")
+            .get(&token_id)
+    }
+
+    /// Base metadata the token is linked to, `None` if token isn't linked to any
+    pub fn mt_metadata_base_by_token_id(&self, token_id: TokenId) -> Option<MtBaseTokenMetadata> {
+        let base_id =
+            self.base_id_by_token.as_ref().expect("Token metadata is not supported").get(&token_id)?;
+        self.mt_metadata_base_by_metadata_id(base_id)
+    }
+
+    /// Base metadata by its ID
+    pub fn mt_metadata_base_by_metadata_id(&self, base_metadata_id: String) -> Option<MtBaseTokenMetadata> {
+        self.base_metadata_by_id
+            .as_ref()
+            .expect("Token metadata is not supported")
+            .get(&base_metadata_id)
+    }
+
+    /// Create or replace base metadata. Tokens already linked to it will show the new metadata.
+    pub fn internal_set_base_metadata(&mut self, base_metadata: &MtBaseTokenMetadata) {
+        base_metadata.assert_valid();
+        self.base_metadata_by_id
+            .as_mut()
+            .expect("Token metadata is not supported")
+            .insert(&base_metadata.id, base_metadata);
+    }
+
+    /// Link existing token to existing base metadata, so both are returned by metadata views.
+    /// Storage is not charged, it's up to the caller. New tokens are linked when minted with
+    /// `base_id`, see [`MultiToken::internal_mint_with_refund`].
+    pub fn internal_link_token_to_base(&mut self, token_id: &TokenId, base_metadata_id: &String) {
+        require!(self.owner_by_id.contains_key(token_id), "Token not found");
+        require!(
+            self.mt_metadata_base_by_metadata_id(base_metadata_id.clone()).is_some(),
+            "Base metadata not found"
+        );
+        self.base_id_by_token
+            .as_mut()
+            .expect("Token metadata is not supported")
+            .insert(token_id, base_metadata_id);
+    }
 }
 
 impl MtContractMetadata {
@@ -63,6 +173,19 @@ impl MtContractMetadata {
     }
 }
 
+impl MtBaseTokenMetadata {
+    pub fn assert_valid(&self) {
+        require!(!self.id.is_empty(), "Base metadata ID cannot be empty");
+        require!(
+            self.reference.is_some() == self.reference_hash.is_some(),
+            "Reference and reference hash must be present"
+        );
+        if let Some(reference_hash) = &self.reference_hash {
+            require!(reference_hash.len() == 32, "Reference hash has to be 32 bytes");
+        }
+    }
+}
+
 impl TokenMetadata {
     pub fn assert_valid(&self) {
         require!(self.media.is_some() == self.media_hash.is_some());
@@ -8,10 +8,10 @@ pub mod metadata;
 
 pub mod enumeration;
 
+pub mod storage_management;
+
 pub mod utils;
 
 pub mod events;
 
-pub mod macros;
-
-pub use macros::*;
\ No newline at end of file
+pub mod macros;
\ No newline at end of file
@@ -0,0 +1,54 @@
+mod storage_impl;
+
+pub use storage_impl::*;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+/// Storage balance of an account
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    /// Total amount deposited for storage
+    pub total: U128,
+    /// Amount that isn't used for storage yet and may be withdrawn
+    pub available: U128,
+}
+
+/// Minimum and maximum amounts that may be deposited for storage
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>,
+}
+
+/// Storage management according to NEP-145
+/// Specs - <https://nomicon.io/Standards/StorageManagement>
+pub trait StorageManagement {
+    /// Deposit NEAR to cover storage of `account_id`, or of the predecessor if it's not provided.
+    /// If `registration_only` is `true`, only the minimum balance is taken and the rest is refunded.
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance;
+
+    /// Withdraw `amount` of available storage balance of the predecessor, or all of it if `amount`
+    /// is not provided. Requires exactly 1 yoctoNEAR.
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance;
+
+    /// Unregister the predecessor and refund its storage balance. Requires exactly 1 yoctoNEAR.
+    /// Panics if `force` is `true`, forced unregistration is not supported.
+    ///
+    /// Returns `true` if the account was registered, `false` otherwise.
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool;
+
+    /// Get minimum and maximum storage balance
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds;
+
+    /// Get storage balance of account, `None` if account is not registered
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance>;
+}
@@ -0,0 +1,176 @@
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, require, AccountId, Balance, Promise, StorageUsage};
+
+use crate::multi_token::core::MultiToken;
+use crate::multi_token::utils::{refund_deposit, refund_released_storage};
+
+use super::{StorageBalance, StorageBalanceBounds, StorageManagement};
+
+/// Storage taken by the storage balance entry of one account. It's counted for the longest
+/// possible account ID (64 bytes + 4 bytes of Borsh length + 1 byte of prefix), two balances
+/// of 16 bytes and 40 bytes of overhead for every record in the trie.
+pub const STORAGE_BALANCE_BYTES: StorageUsage = 64 + 4 + 1 + 16 * 2 + 40;
+
+impl MultiToken {
+    /// Minimum storage balance, covers the storage balance entry itself
+    pub fn internal_storage_balance_min(&self) -> Balance {
+        env::storage_byte_cost() * Balance::from(STORAGE_BALANCE_BYTES)
+    }
+
+    /// Pay for `storage_used` bytes on behalf of `account_id`. If account has storage balance,
+    /// its available balance is used and the whole attached deposit is refunded to the predecessor,
+    /// otherwise the attached deposit is used and the rest is refunded.
+    pub fn internal_pay_storage(&mut self, account_id: &AccountId, storage_used: StorageUsage) {
+        if self.storage_balances.contains_key(account_id) {
+            self.internal_charge_storage_balance(account_id, storage_used);
+            let deposit = env::attached_deposit();
+            if deposit > 0 {
+                Promise::new(env::predecessor_account_id()).transfer(deposit);
+            }
+        } else {
+            refund_deposit(storage_used);
+        }
+    }
+
+    /// Pay for `storage_used` bytes from the available storage balance of `account_id`.
+    /// Panics if account is not registered or doesn't have enough available balance.
+    pub fn internal_charge_storage_balance(
+        &mut self,
+        account_id: &AccountId,
+        storage_used: StorageUsage,
+    ) {
+        let mut balance = self.storage_balances.get(account_id).unwrap_or_else(|| {
+            env::panic_str(
+                format!("The account {} is not registered for storage", account_id).as_str(),
+            )
+        });
+
+        let cost = env::storage_byte_cost() * Balance::from(storage_used);
+        require!(
+            cost <= balance.available.0,
+            format!("Not enough storage balance, {} yoctoNEAR is required", cost)
+        );
+        balance.available = (balance.available.0 - cost).into();
+        self.storage_balances.insert(account_id, &balance);
+    }
+
+    /// Give back the cost of `storage_released` bytes to `account_id`. If account has storage
+    /// balance, it's increased by no more than was charged from it, and the rest of the cost
+    /// is transferred to the account. Storage may be paid from the attached deposit before
+    /// the account was registered, so it can't be credited to the storage balance completely.
+    pub fn internal_release_storage(&mut self, account_id: &AccountId, storage_released: StorageUsage) {
+        match self.storage_balances.get(account_id) {
+            Some(mut balance) => {
+                let cost = env::storage_byte_cost() * Balance::from(storage_released);
+                let charged = (balance.total.0 - balance.available.0)
+                    .saturating_sub(self.internal_storage_balance_min());
+                let credit = std::cmp::min(cost, charged);
+                balance.available = (balance.available.0 + credit).into();
+                self.storage_balances.insert(account_id, &balance);
+
+                if cost > credit {
+                    Promise::new(account_id.clone()).transfer(cost - credit);
+                }
+            }
+            None => refund_released_storage(account_id.clone(), storage_released),
+        }
+    }
+}
+
+impl StorageManagement for MultiToken {
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let registration_only = registration_only.unwrap_or(false);
+
+        let balance = match self.storage_balances.get(&account_id) {
+            Some(balance) if registration_only => {
+                if amount > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(amount);
+                }
+                balance
+            }
+            Some(balance) => StorageBalance {
+                total: (balance.total.0 + amount).into(),
+                available: (balance.available.0 + amount).into(),
+            },
+            None => {
+                let min = self.internal_storage_balance_min();
+                require!(
+                    amount >= min,
+                    format!("Must attach at least {} yoctoNEAR to register", min)
+                );
+
+                let deposit = if registration_only { min } else { amount };
+                let refund = amount - deposit;
+                if refund > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(refund);
+                }
+
+                StorageBalance { total: deposit.into(), available: (deposit - min).into() }
+            }
+        };
+
+        self.storage_balances.insert(&account_id, &balance);
+        balance
+    }
+
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+
+        let mut balance = self
+            .storage_balances
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str(format!("The account {} is not registered", account_id).as_str()));
+
+        let amount = amount.map(|amount| amount.0).unwrap_or(balance.available.0);
+        require!(amount <= balance.available.0, "Amount is greater than available storage balance");
+
+        balance.total = (balance.total.0 - amount).into();
+        balance.available = (balance.available.0 - amount).into();
+        self.storage_balances.insert(&account_id, &balance);
+
+        if amount > 0 {
+            Promise::new(account_id).transfer(amount);
+        }
+
+        balance
+    }
+
+    /// Forced unregistration is not supported: all registrations and approvals paid from
+    /// the storage balance have to be removed before unregistering.
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_one_yocto();
+        require!(!force.unwrap_or(false), "Forced unregistration is not supported");
+        let account_id = env::predecessor_account_id();
+
+        let balance = match self.storage_balances.get(&account_id) {
+            Some(balance) => balance,
+            None => return false,
+        };
+
+        let used = balance.total.0 - balance.available.0;
+        require!(
+            used <= self.internal_storage_balance_min(),
+            "Can't unregister the account that still uses storage"
+        );
+
+        self.storage_balances.remove(&account_id);
+        Promise::new(account_id).transfer(balance.total.0);
+
+        true
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds { min: self.internal_storage_balance_min().into(), max: None }
+    }
+
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.storage_balances.get(&account_id)
+    }
+}
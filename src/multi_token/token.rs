@@ -1,7 +1,10 @@
 use crate::multi_token::metadata::TokenMetadata;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
-pub use near_sdk::{AccountId, Balance};
+use near_sdk::env;
+pub use near_sdk::AccountId;
 use std::collections::HashMap;
 
 /// Type alias for convenience
@@ -10,19 +13,43 @@ pub type TokenId = String;
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, BorshDeserialize, BorshSerialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Approval {
-    pub amount: u128,
+    pub amount: U128,
     pub approval_id: u64,
+    /// Block timestamp in nanoseconds after which the approval can't be used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
 }
 
+impl Approval {
+    pub fn is_expired(&self) -> bool {
+        matches!(self.expires_at, Some(expires_at) if env::block_timestamp() >= expires_at)
+    }
+}
+
+/// Approval with the token, the holder that granted it and the approved account
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenApproval {
+    pub token_id: TokenId,
+    pub owner_id: AccountId,
+    pub approved_account_id: AccountId,
+    #[serde(flatten)]
+    pub approval: Approval,
+}
+
+/// Approvals of a token by holder that granted them and approved account
+pub type ApprovalsByOwner = UnorderedMap<(AccountId, AccountId), Approval>;
+
 /// Info on individual token
 #[derive(Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
 pub struct Token {
     pub token_id: String,
     pub owner_id: AccountId,
     /// Total amount generated
-    pub supply: u128,
-    pub balances: HashMap<AccountId, Balance>,
+    pub supply: U128,
+    pub balances: HashMap<AccountId, U128>,
     pub metadata: Option<TokenMetadata>,
+    /// Approvals granted by `owner_id`
     pub approvals: Option<HashMap<AccountId, Approval>>,
     pub next_approval_id: Option<u64>,
 }
@@ -5,6 +5,11 @@ use std::{
 
 use near_sdk::{env, require, AccountId, Balance, CryptoHash, Promise};
 
+use crate::multi_token::token::TokenId;
+
+/// Max length of token ID chosen by caller
+pub const MAX_TOKEN_ID_LEN: usize = 64;
+
 pub fn hash_account_id(account_id: &AccountId) -> CryptoHash {
     let mut hash = CryptoHash::default();
     hash.copy_from_slice(&env::sha256(account_id.as_bytes()));
@@ -31,6 +36,14 @@ pub fn refund_deposit(storage_used: u64) {
     refund_deposit_to_account(storage_used, env::predecessor_account_id())
 }
 
+/// Send the cost of released storage back to the account that paid for it
+pub fn refund_released_storage(account_id: AccountId, storage_released: u64) {
+    let refund = env::storage_byte_cost() * Balance::from(storage_released);
+    if refund > 0 {
+        Promise::new(account_id).transfer(refund);
+    }
+}
+
 // TODO: need a way for end users to determine how much an approval will cost.
 pub fn bytes_for_approved_account_id(account_id: &AccountId) -> u64 {
     // The extra 4 bytes are coming from Borsh serialization to store the length of the string.
@@ -53,7 +66,21 @@ impl Display for Entity {
 }
 
 pub fn expect_approval<T>(o: Option<T>, entity: Entity) -> T {
-    o.unwrap_or_else(|| panic!("Approval Management is not supported by {}", entity.to_string()))
+    o.unwrap_or_else(|| panic!("Approval Management is not supported by {}", entity))
+}
+
+/// Token ID must be non-empty, not longer than [`MAX_TOKEN_ID_LEN`] and may contain only
+/// ASCII letters, digits and `-`, `_`, `.`, `:` characters
+pub fn assert_valid_token_id(token_id: &TokenId) {
+    require!(!token_id.is_empty(), "Token ID cannot be empty");
+    require!(
+        token_id.len() <= MAX_TOKEN_ID_LEN,
+        format!("Token ID cannot be longer than {} characters", MAX_TOKEN_ID_LEN)
+    );
+    require!(
+        token_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')),
+        "Token ID contains invalid characters"
+    );
 }
 
 pub fn unauthorized_assert(account_id: &AccountId) {